@@ -57,11 +57,34 @@
             help: 'Number of rows per file.',
             type: 'size',
         },
+        stream: {
+            help: 'Generate rows continuously until interrupted.',
+            type: 'bool',
+        },
+        rows_per_second: {
+            long: 'rows-per-second',
+            help: 'Maximum number of rows of the main table generated per second.',
+            type: 'size',
+        },
+        bytes_per_second: {
+            long: 'bytes-per-second',
+            help: 'Maximum number of bytes written per second (before compression).',
+            type: 'size',
+        },
+        file_range: {
+            long: 'file-range',
+            help: 'Only generate the files with index in this range (e.g. 1..5, 5..=8 or 9..).',
+        },
         size: {
             short: 'z',
             help: 'Target pre-compressed size of each file.',
             type: 'size',
         },
+        compressed_size: {
+            long: 'compressed-size',
+            help: 'Target compressed (on-disk) size of each file.',
+            type: 'size',
+        },
         escape_backslash: {
             long: 'escape-backslash',
             help: 'Escape backslashes when writing a string.',
@@ -82,6 +105,11 @@
             type: $.choices(['chacha12', 'chacha20', 'hc128', 'isaac', 'isaac64', 'xorshift', 'pcg32', 'step']),
             default: 'hc128',
         },
+        per_row_rng: {
+            long: 'per-row-rng',
+            help: 'Seed a separate random number generator for every row.',
+            type: 'bool',
+        },
         quiet: {
             short: 'q',
             help: 'Disable progress bar.',
@@ -93,9 +121,38 @@
         format: {
             short: 'f',
             help: 'Output format.',
-            type: $.choices(['sql', 'csv', 'sql-insert-set']),
+            type: $.choices(['sql', 'csv', 'sql-insert-set', 'json-lines', 'parquet', 'pg-copy', 'pg-copy-binary', 'mysql-tsv', 'sqlite']),
             default: 'sql',
         },
+        dialect: {
+            help: 'SQL dialect of the target database, used to render literals and quoted identifiers.',
+            type: $.choices(['mysql', 'postgresql', 'sqlite', 'sqlserver', 'oracle']),
+        },
+        insert_mode: {
+            long: 'insert-mode',
+            help: 'Kind of statement used to insert rows in the SQL formats.',
+            type: $.choices(['insert', 'insert-ignore', 'replace', 'upsert']),
+            default: 'insert',
+        },
+        conflict_target: {
+            long: 'conflict-target',
+            help: 'Columns of the unique key which inserted rows may conflict on, as [TABLE=]COLUMN,...',
+        },
+        update_columns: {
+            long: 'update-columns',
+            help: 'Columns to update when inserted rows conflict with existing ones, as [TABLE=]COLUMN,...',
+        },
+        transaction_size: {
+            long: 'transaction-size',
+            help: 'Wrap every N INSERT statements of the SQL formats in a transaction.',
+            type: 'int',
+        },
+        preamble: {
+            help: 'SQL statements to write at the beginning of every data file.',
+        },
+        postamble: {
+            help: 'SQL statements to write at the end of every data file.',
+        },
         format_true: {
             long: 'format-true',
             help: 'The keyword to print for a boolean TRUE value.',
@@ -112,21 +169,79 @@
             help: 'Include column names or headers in the output.',
             type: 'bool',
         },
+        csv_delimiter: {
+            long: 'csv-delimiter',
+            help: 'The character separating fields in the CSV format.',
+        },
+        csv_quote: {
+            long: 'csv-quote',
+            help: 'The character enclosing quoted fields in the CSV format.',
+        },
+        csv_escape: {
+            long: 'csv-escape',
+            help: 'The character escaping special characters in the CSV format.',
+        },
+        csv_quote_policy: {
+            long: 'csv-quote-policy',
+            help: 'When to enclose fields in quotes in the CSV format.',
+            type: $.choices(['strings', 'always', 'when-needed', 'never', 'non-numeric']),
+        },
+        csv_line_terminator: {
+            long: 'csv-line-terminator',
+            help: 'The record terminator of the CSV format.',
+            type: $.choices(['lf', 'crlf']),
+        },
+        csv_bom: {
+            long: 'csv-bom',
+            help: 'Write the UTF-8 byte order mark at the beginning of every CSV file.',
+            type: 'bool',
+        },
+        sqlite_file: {
+            long: 'sqlite-file',
+            help: 'Path of the database file of the sqlite format, relative to the output directory.',
+        },
+        sqlite_overwrite: {
+            long: 'sqlite-overwrite',
+            help: 'Replace an existing database file of the sqlite format when creating the tables.',
+            type: 'bool',
+        },
         compression: {
             short: 'c',
             help: 'Compress data output.',
-            type: $.choices(['gzip', 'xz', 'zstd']),
+            type: $.choices(['gzip', 'xz', 'zstd', 'lz4', 'bzip2', 'brotli', 'snappy']),
         },
         compress_level: {
             long: 'compress-level',
-            help: 'Compression level (0-9 for gzip and xz, 1-21 for zstd).',
+            help: 'Compression level (0-9 for gzip and xz, 1-21 for zstd, 0-12 for lz4, 1-9 for bzip2, 0-11 for brotli).',
             type: 'int',
             default: '6',
         },
+        compress_threads: {
+            long: 'compress-threads',
+            help: 'Number of threads compressing each data file, 0 for the number of CPUs.',
+            type: 'int',
+            default: '1',
+        },
         components: {
             help: 'Components to write.',
             type: $.choices(['schema', 'table', 'data'], multiple=true),
             default: 'table,data',
         },
+        manifest: {
+            help: 'Write manifest.json listing every data file with its row counts, sizes and checksum.',
+            type: 'bool',
+        },
+        resume: {
+            help: 'Skip the files completed by an interrupted run with the same arguments.',
+            type: 'bool',
+        },
+        clean_temp_files: {
+            help: 'Remove the temporary data files left by an interrupted run.',
+            type: 'bool',
+        },
+        estimate: {
+            help: 'Print the projected rows, sizes and files instead of generating them.',
+            type: 'bool',
+        },
     },
 }
@@ -11,15 +11,19 @@ use std::{
     ffi::OsString,
 };
 
-#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ArgType {
     Bool,
+    #[default]
     Str,
     Int,
     Size,
     Float,
-    Choices { choices: Vec<String>, multiple: bool },
+    Choices {
+        choices: Vec<String>,
+        multiple: bool,
+    },
 }
 
 impl ArgType {
@@ -47,12 +51,6 @@ impl ArgType {
     }
 }
 
-impl Default for ArgType {
-    fn default() -> Self {
-        Self::Str
-    }
-}
-
 #[derive(Deserialize, Default, Debug)]
 #[serde(default)]
 pub struct Arg {
@@ -46,8 +46,8 @@ impl Writer for TableWriter {
         Ok(())
     }
 
-    fn write_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
-        self.write_row_separator()
+    fn write_header(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        self.write_row_separator(schema)
     }
 
     fn write_value_header(&mut self, _: &str) -> Result<(), S<Error>> {
@@ -58,13 +58,17 @@ impl Writer for TableWriter {
         Ok(())
     }
 
-    fn write_row_separator(&mut self) -> Result<(), S<Error>> {
+    fn write_row_separator(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
         let columns = self.rows.last().map_or(0, |r| r.len());
         self.rows.push(Vec::with_capacity(columns));
         Ok(())
     }
 
-    fn write_trailer(&mut self) -> Result<(), S<Error>> {
+    fn write_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_file_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
         Ok(())
     }
 }
@@ -321,18 +321,18 @@ mod tests {
     fn test_feistel_is_permutation() {
         let mut feistel = Feistel::prepare(256);
         feistel.seed = [
-            0x09f7ee6201f67de4,
-            0x536db2a4c7976eb7,
-            0x15640fedcdd650fe,
-            0x764ba03cbe3bccc8,
-            0xcdca39b28fa0e573,
-            0x57e9d5fffeb5f4e4,
-            0xac82463f11dcfe32,
-            0x820461c4207b305b,
+            0x09f7_ee62_01f6_7de4,
+            0x536d_b2a4_c797_6eb7,
+            0x1564_0fed_cdd6_50fe,
+            0x764b_a03c_be3b_ccc8,
+            0xcdca_39b2_8fa0_e573,
+            0x57e9_d5ff_feb5_f4e4,
+            0xac82_463f_11dc_fe32,
+            0x8204_61c4_207b_305b,
         ];
         let shuffled = (0..256).map(|i| feistel.get(i)).collect::<Vec<_>>();
         let mut sorted = shuffled.clone();
-        sorted.sort();
+        sorted.sort_unstable();
         assert!(sorted.iter().copied().eq(0..256), "{sorted:?}");
         assert_ne!(shuffled, sorted);
     }
@@ -313,20 +313,18 @@ impl ByteString {
             .filter_map(|(b, i)| is_utf8_leading_byte(*b).then_some(i))
             .fuse();
 
-        let start;
-        let end;
         if let Some(n) = r.start.checked_sub(self.ascii_len) {
             // this branch means `r.start > self.ascii_len`.
-            start = it.nth(n).unwrap_or(input_len);
+            let start = it.nth(n).unwrap_or(input_len);
             // note that `it.nth(n)` positions `it` at index `n+1` after execution.
             // therefore, we want `it.nth(r.end - r.start - 1)` to read the end index.
             let range_len = r.len().checked_sub(1);
-            end = range_len.map_or(start, |len| it.nth(len).unwrap_or(input_len));
+            let end = range_len.map_or(start, |len| it.nth(len).unwrap_or(input_len));
+            start..end
         } else {
-            start = r.start;
-            end = it.nth(r.end - self.ascii_len).unwrap_or(input_len);
+            let end = it.nth(r.end - self.ascii_len).unwrap_or(input_len);
+            r.start..end
         }
-        start..end
     }
 
     /// Replaces the substring at `range` by a `replacement` string.
@@ -361,6 +359,8 @@ impl ByteString {
 }
 
 #[cfg(test)]
+// ALLOW_REASON: the test cases are written as tuples for brevity.
+#[allow(clippy::type_complexity)]
 mod tests {
     use super::*;
 
@@ -398,14 +398,14 @@ mod tests {
 
             {
                 use std::io::Write;
-                write!(&mut target_clone_2, "{}", string).unwrap();
+                write!(&mut target_clone_2, "{string}").unwrap();
             }
             assert_eq!(target_clone_2.encoding(), encoding);
             assert_eq!(target_clone_2.as_bytes(), bytes);
 
             {
                 use std::fmt::Write;
-                write!(&mut target_clone_3, "{}", string).unwrap();
+                write!(&mut target_clone_3, "{string}").unwrap();
             }
             assert_eq!(target_clone_3.encoding(), encoding);
             assert_eq!(target_clone_3.as_bytes(), bytes);
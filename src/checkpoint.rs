@@ -0,0 +1,144 @@
+//! Resuming interrupted runs from `checkpoint.jsonl`.
+//!
+//! The first line of the checkpoint identifies the run by its seed, template and arguments. Every
+//! following line records a file generator thread which has completed, together with the data files
+//! it produced, so a resumed run skips those threads and still lists their files.
+
+use crate::{
+    cli::{PathResultExt as _, Seed, WrittenFile},
+    error::Error,
+    span::{S, SpanExt as _},
+};
+use data_encoding::HEXLOWER;
+use rand::{Rng as _, rngs::OsRng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions, read_to_string},
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+
+/// The first line of `checkpoint.jsonl`, identifying the run.
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckpointHeader {
+    /// The seed of the random number generators.
+    seed: Seed,
+    /// The SHA-256 checksum of the template in hex.
+    template_sha256: String,
+    /// The command line arguments, excluding those not affecting the output.
+    args: serde_json::Value,
+}
+
+/// A line of `checkpoint.jsonl` after the header, recording a completed file generator thread.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckpointEntry {
+    /// Index of the file generator thread.
+    pub file_index: u32,
+    /// The data files it produced.
+    pub files: Vec<WrittenFile>,
+}
+
+/// The checkpoint of a resumable run, i.e. the `checkpoint.jsonl` file recording every completed
+/// file generator thread.
+#[derive(Debug)]
+pub struct Checkpoint {
+    /// Path of the checkpoint file.
+    path: PathBuf,
+    /// The checkpoint file opened for appending.
+    file: Mutex<File>,
+    /// The seed of the random number generators, possibly taken from the previous run.
+    pub seed: Seed,
+    /// The data files produced by the file generator threads completed in previous runs.
+    pub completed: HashMap<u32, Vec<WrittenFile>>,
+}
+
+impl Checkpoint {
+    /// Opens the checkpoint file in the output directory, creating it if it does not exist.
+    ///
+    /// If the file is written by a previous run, the `args` and `template` must be the same as
+    /// those of that run, and the seed of that run is reused.
+    pub fn open(
+        out_dir: &Path,
+        seed: Option<Seed>,
+        mut args: serde_json::Value,
+        template: &str,
+    ) -> Result<Self, S<Error>> {
+        if let Some(args) = args.as_object_mut() {
+            for key in ["jobs", "quiet", "seed", "clean_temp_files"] {
+                args.remove(key);
+            }
+        }
+        let template_sha256 = HEXLOWER.encode(&Sha256::digest(template));
+
+        let path = out_dir.join("checkpoint.jsonl");
+        let content = match read_to_string(&path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            content => content.with_path("read checkpoint file", &path)?,
+        };
+        let mut file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&path)
+            .with_path("open checkpoint file", &path)?;
+        // The last line may be incomplete if the previous run was killed while writing it.
+        let content = &content[..content.rfind('\n').map_or(0, |i| i + 1)];
+        file.set_len(content.len() as u64)
+            .with_path("truncate checkpoint file", &path)?;
+
+        let mut lines = content.lines();
+        let (seed, completed) = if let Some(line) = lines.next() {
+            let header: CheckpointHeader = serde_json::from_str(line)
+                .map_err(io::Error::from)
+                .with_path("parse checkpoint file", &path)?;
+            if header.template_sha256 != template_sha256
+                || header.args != args
+                || seed.is_some_and(|seed| seed != header.seed)
+            {
+                return Err(Error::InvalidArguments(format!(
+                    "cannot resume from {} written with a different template, seed or arguments",
+                    path.display()
+                ))
+                .no_span());
+            }
+            let completed = lines
+                .map(|line| {
+                    let entry = serde_json::from_str::<CheckpointEntry>(line)?;
+                    Ok((entry.file_index, entry.files))
+                })
+                .collect::<Result<_, serde_json::Error>>()
+                .map_err(io::Error::from)
+                .with_path("parse checkpoint file", &path)?;
+            (header.seed, completed)
+        } else {
+            let header = CheckpointHeader {
+                seed: seed.unwrap_or_else(|| OsRng.r#gen()),
+                template_sha256,
+                args,
+            };
+            let mut line = serde_json::to_vec(&header).expect("checkpoint header should be serializable");
+            line.push(b'\n');
+            file.write_all(&line).with_path("write checkpoint file", &path)?;
+            (header.seed, HashMap::new())
+        };
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            seed,
+            completed,
+        })
+    }
+
+    /// Records that a file generator thread is completed.
+    pub fn record(&self, entry: &CheckpointEntry) -> Result<(), S<Error>> {
+        let mut line = serde_json::to_vec(entry)
+            .map_err(io::Error::from)
+            .with_path("write checkpoint file", &self.path)?;
+        line.push(b'\n');
+        // Each line is written in a single call, so concurrent writers do not interleave.
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        file.write_all(&line).with_path("write checkpoint file", &self.path)
+    }
+}
@@ -1,19 +1,41 @@
 //! CLI driver of `dbgen`.
 
+#[cfg(feature = "mysql")]
+use crate::mysql::{MySqlDatabase, MySqlInsertWriter};
+#[cfg(feature = "parquet")]
+use crate::parquet::ParquetWriter;
+#[cfg(feature = "postgres")]
+use crate::postgres::{PostgresCopyWriter, PostgresDatabase};
+#[cfg(feature = "s3")]
+use crate::s3::{S3Bucket, S3Writer};
+#[cfg(feature = "sqlite")]
+use crate::sqlite::{SqliteDatabase, SqliteWriter};
 use crate::{
+    checkpoint::{Checkpoint, CheckpointEntry},
     error::Error,
+    estimate::print_estimate,
     eval::{CompileContext, Schema, State, Table},
-    format::{CsvFormat, Format, Options, SqlFormat, SqlInsertSetFormat},
+    format::{
+        ConflictClause, CsvFormat, CsvLineTerminator, CsvOptions, CsvQuotePolicy, Dialect, Format, InsertMode,
+        JsonLinesFormat, MySqlTsvFormat, Options, PgCopyFormat, SqlFormat, SqlInsertSetFormat,
+    },
     lexctr::LexCtr,
-    parser::{QName, Template},
+    manifest::{Manifest, ManifestFile},
+    parallel_compress::ParallelCompressor,
+    parser::{Expr, QName, Template},
+    partition::{DEFAULT_MAX_OPEN_PARTITIONS, Partitioning, partition_dir_name, resolve_partitionings},
+    pending_file::{PendingFile, remove_temp_files},
+    pg_copy::PgCopyBinaryWriter,
     span::{Registry, ResultExt, S, SpanExt},
+    stream::{RateLimit, STDOUT_CHUNKS_PER_FILE, StdoutChunkWriter, write_chunks_to_output},
     value::{TIMESTAMP_FORMAT, Value},
     writer::{self, Writer},
 };
 
+use bzip2::write::BzEncoder;
 use chrono::{NaiveDateTime, ParseResult, Utc};
 use clap::{Parser, ValueEnum};
-use data_encoding::{DecodeError, DecodeKind, HEXLOWER_PERMISSIVE};
+use data_encoding::{DecodeError, DecodeKind, HEXLOWER, HEXLOWER_PERMISSIVE};
 use flate2::write::GzEncoder;
 use muldiv::MulDiv;
 use pbr::{MultiBar, Units};
@@ -23,36 +45,46 @@ use rand::{
     rngs::{OsRng, mock::StepRng},
 };
 use rayon::{
-    ThreadPoolBuilder,
+    ThreadPool, ThreadPoolBuilder,
     iter::{IntoParallelIterator, ParallelIterator},
 };
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest as _, Sha256};
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     convert::TryInto,
     fmt,
     fs::{File, create_dir_all, read_to_string},
     io::{self, BufWriter, Read, Write, sink, stdin},
-    mem,
+    iter, mem,
+    net::TcpStream,
+    num::{NonZeroU64, NonZeroUsize},
     path::{Path, PathBuf},
     str::FromStr,
-    sync::atomic::{AtomicBool, AtomicU64, Ordering},
-    thread::{sleep, spawn},
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{SyncSender, channel, sync_channel},
+    },
+    thread::{available_parallelism, sleep, spawn},
     time::Duration,
 };
-use xz2::write::XzEncoder;
+use xz2::{
+    stream::{Check, MtStreamBuilder},
+    write::XzEncoder,
+};
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
-struct RowArgs {
+pub(crate) struct RowArgs {
     /// Number of files to generate (*k*).
-    files_count: u32,
+    pub(crate) files_count: u32,
     /// Number of INSERT statements per normal file (*n*).
-    inserts_count: u32,
+    pub(crate) inserts_count: u32,
     /// Number of INSERT statements in the last file.
     last_file_inserts_count: u32,
     /// Number of rows per INSERT statement (*r*).
-    rows_count: u32,
+    pub(crate) rows_count: u32,
     /// Number of rows in the final INSERT statement in a normal file.
     final_insert_rows_count: u32,
     /// Number of rows in the final INSERT statement in the last file.
@@ -61,12 +93,12 @@ struct RowArgs {
     /// Number of rows per normal file (*R*).
     ///
     /// Must be same as `(n - 1) * r + final_insert_rows_count`.
-    rows_per_file: u64,
+    pub(crate) rows_per_file: u64,
 
     /// Total number of rows to generate (*N*).
     ///
     /// Must be same as `(k - 1) * R + (last_file_inserts_count - 1) * r + last_file_final_insert_rows_count`.
-    total_count: u64,
+    pub(crate) total_count: u64,
 }
 
 /// Arguments to the `dbgen` CLI program.
@@ -91,7 +123,9 @@ pub struct Args {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schema_name: Option<String>,
 
-    /// Output directory.
+    /// Output directory, `-` to write everything to the standard output, a `tcp://HOST:PORT` address
+    /// to send everything through a TCP connection, or a `postgres://` or `mysql://` URL to load the
+    /// rows directly into a database.
     #[arg(short, long)]
     pub out_dir: PathBuf,
 
@@ -123,6 +157,32 @@ pub struct Args {
     #[arg(short = 'N', long, value_parser = parse_row_count, conflicts_with_all(&["files_count", "last_file_inserts_count", "last_insert_rows_count"]))]
     pub total_count: Option<u64>,
 
+    /// Number of rows of the root tables with their own row counts, as `TABLE=N,...`, overriding
+    /// the counts declared in the template.
+    #[arg(long, value_delimiter(','))]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub table_rows: Vec<String>,
+
+    /// Generate rows continuously until interrupted, instead of a fixed number of rows.
+    #[arg(long, conflicts_with_all(&["total_count", "files_count", "last_file_inserts_count", "last_insert_rows_count", "file_range"]))]
+    #[serde(skip_serializing_if = "is_false")]
+    pub stream: bool,
+
+    /// Maximum number of rows of the main table generated per second.
+    #[arg(long, value_parser = parse_row_count)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows_per_second: Option<u64>,
+
+    /// Maximum number of bytes written per second (before compression).
+    #[arg(long, value_parser = |s: &str| parse_size::parse_size(s))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_per_second: Option<u64>,
+
+    /// Only generate the files with index in this range (e.g. `1..5`, `5..=8` or `9..`).
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_range: Option<FileRange>,
+
     /// Number of rows per file generator thread.
     #[arg(short = 'R', long, value_parser = parse_row_count, conflicts_with_all(&["inserts_count"]))]
     pub rows_per_file: Option<u64>,
@@ -131,6 +191,22 @@ pub struct Args {
     #[arg(short = 'z', long, value_parser = |s: &str| parse_size::parse_size(s))]
     pub size: Option<u64>,
 
+    /// Target compressed (on-disk) size of each file.
+    #[arg(long, value_parser = |s: &str| parse_size::parse_size(s), conflicts_with = "size")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compressed_size: Option<u64>,
+
+    /// Write the rows into a `TABLE/COLUMN=VALUE/` directory for every distinct value of these
+    /// columns, as `[TABLE=]COLUMN,...`.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub partition_by: Vec<String>,
+
+    /// Maximum number of partition files kept open by every file generator thread (default: 64).
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_open_partitions: Option<NonZeroUsize>,
+
     /// Escape backslashes when writing a string.
     #[arg(long)]
     #[serde(skip_serializing_if = "is_false")]
@@ -165,6 +241,12 @@ pub struct Args {
     #[serde(skip_serializing_if = "is_hc128")]
     pub rng: RngName,
 
+    /// Seed a separate random number generator for every row, so the rows do not depend on how
+    /// they are split into files.
+    #[arg(long, conflicts_with = "rng")]
+    #[serde(skip_serializing_if = "is_false")]
+    pub per_row_rng: bool,
+
     /// Disable progress bar.
     #[arg(short, long)]
     #[serde(skip_serializing_if = "is_false")]
@@ -190,6 +272,41 @@ pub struct Args {
     #[serde(skip_serializing_if = "is_sql")]
     pub format: FormatName,
 
+    /// SQL dialect of the target database, used to render literals and quoted identifiers.
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<Dialect>,
+
+    /// Kind of statement used to insert rows in the SQL formats.
+    #[arg(long, value_enum, default_value = "insert")]
+    #[serde(skip_serializing_if = "is_insert")]
+    pub insert_mode: InsertMode,
+
+    /// Columns of the unique key which inserted rows may conflict on, as `[TABLE=]COLUMN,...`.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Vec::is_empty", deserialize_with = "deserialize_one_or_many")]
+    pub conflict_target: Vec<String>,
+
+    /// Columns to update when inserted rows conflict with existing ones, as `[TABLE=]COLUMN,...`.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Vec::is_empty", deserialize_with = "deserialize_one_or_many")]
+    pub update_columns: Vec<String>,
+
+    /// Wrap every N INSERT statements of the SQL formats in a transaction.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_size: Option<NonZeroU64>,
+
+    /// SQL statements to write at the beginning of every data file.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preamble: Option<String>,
+
+    /// SQL statements to write at the end of every data file.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postamble: Option<String>,
+
     /// The keyword to print for a boolean TRUE value.
     #[arg(long)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -210,21 +327,104 @@ pub struct Args {
     #[serde(skip_serializing_if = "is_false")]
     pub headers: bool,
 
+    /// The character separating fields in the CSV format (default: `,`).
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv_delimiter: Option<char>,
+
+    /// The character enclosing quoted fields in the CSV format (default: `"`).
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv_quote: Option<char>,
+
+    /// The character escaping special characters in the CSV format (default: double the quote).
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv_escape: Option<char>,
+
+    /// When to enclose fields in quotes in the CSV format (default: strings).
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv_quote_policy: Option<CsvQuotePolicy>,
+
+    /// The record terminator of the CSV format (default: lf).
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv_line_terminator: Option<CsvLineTerminator>,
+
+    /// Write the UTF-8 byte order mark at the beginning of every CSV file.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub csv_bom: bool,
+
+    /// Path of the database file of the sqlite format, relative to the output directory (default: data.db).
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sqlite_file: Option<PathBuf>,
+
+    /// Replace an existing database file of the sqlite format when creating the tables.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub sqlite_overwrite: bool,
+
+    /// Number of INSERT statements sent together when loading directly into a database (default: 1).
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<NonZeroU64>,
+
+    /// Number of times a batch failing with a transient error is retried when loading directly into a
+    /// database (default: 3).
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+
+    /// SQL statements executed on every connection when loading directly into a database.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_init: Option<String>,
+
     /// Compress data output.
     #[arg(short, long, value_enum)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compression: Option<CompressionName>,
 
-    /// Compression level (0-9 for gzip and xz, 1-21 for zstd).
+    /// Compression level (0-9 for gzip and xz, 1-21 for zstd, 0-12 for lz4, 1-9 for bzip2, 0-11 for brotli).
     #[arg(long, default_value = "6")]
     #[serde(skip_serializing_if = "is_six")]
     pub compress_level: u8,
 
+    /// Number of threads compressing each data file, 0 for the number of CPUs.
+    #[arg(long, default_value = "1")]
+    #[serde(skip_serializing_if = "is_one")]
+    pub compress_threads: u32,
+
     /// Components to write.
     #[arg(long, value_enum, value_delimiter(','), default_value = "table,data", conflicts_with_all(&["no_schemas", "no_data"]))]
     #[serde(skip_serializing_if = "is_default_components")]
     pub components: Vec<ComponentName>,
 
+    /// Write `manifest.json` listing every data file with its row counts, sizes and checksum.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub manifest: bool,
+
+    /// Record the completed files in `checkpoint.jsonl`, and skip the files already recorded there
+    /// by a previous (interrupted) run with the same arguments.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub resume: bool,
+
+    /// Remove the temporary data files left in the output directory by an interrupted run.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub clean_temp_files: bool,
+
+    /// Do not write anything, but generate a sample of rows and print the projected number of rows,
+    /// sizes and files of the output.
+    #[arg(long, conflicts_with("stream"))]
+    #[serde(skip_serializing_if = "is_false")]
+    pub estimate: bool,
+
     /// Do not generate schema files (the CREATE TABLE *.sql files).
     #[arg(long, hide(true))]
     #[serde(skip)]
@@ -254,26 +454,58 @@ impl Default for Args {
             last_file_inserts_count: None,
             last_insert_rows_count: None,
             total_count: None,
+            table_rows: Vec::new(),
+            stream: false,
+            rows_per_second: None,
+            bytes_per_second: None,
+            file_range: None,
             rows_per_file: None,
             size: None,
+            compressed_size: None,
+            partition_by: Vec::new(),
+            max_open_partitions: None,
             escape_backslash: false,
             template: None,
             template_string: None,
             seed: None,
             jobs: 0,
             rng: RngName::Hc128,
+            per_row_rng: false,
             quiet: false,
             time_zone: "UTC".to_owned(),
             zoneinfo: PathBuf::from("/usr/share/zoneinfo"),
             now: None,
             format: FormatName::Sql,
+            dialect: None,
+            insert_mode: InsertMode::Insert,
+            conflict_target: Vec::new(),
+            update_columns: Vec::new(),
+            transaction_size: None,
+            preamble: None,
+            postamble: None,
             format_true: None,
             format_false: None,
             format_null: None,
             headers: false,
+            csv_delimiter: None,
+            csv_quote: None,
+            csv_escape: None,
+            csv_quote_policy: None,
+            csv_line_terminator: None,
+            csv_bom: false,
+            sqlite_file: None,
+            sqlite_overwrite: false,
+            batch_size: None,
+            max_retries: None,
+            session_init: None,
             compression: None,
             compress_level: 6,
+            compress_threads: 1,
             components: vec![ComponentName::Table, ComponentName::Data],
+            manifest: false,
+            resume: false,
+            clean_temp_files: false,
+            estimate: false,
             no_schemas: false,
             no_data: false,
             initialize: Vec::new(),
@@ -322,18 +554,50 @@ fn is_sql(format: &FormatName) -> bool {
     *format == FormatName::Sql
 }
 
+// ALLOW_REASON: the arguments of serde helper must be references.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_insert(mode: &InsertMode) -> bool {
+    *mode == InsertMode::Insert
+}
+
 fn is_default_components(components: &[ComponentName]) -> bool {
     ComponentName::union_all(components.iter().copied()) == ComponentName::Table as u8 | ComponentName::Data as u8
 }
 
+/// Deserializes a list of strings, also accepting a single string such as the `str` arguments of
+/// `dbdbgen`.
+fn deserialize_one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
 fn parse_row_count(input: &str) -> Result<u64, parse_size::Error> {
     use parse_size::{ByteSuffix, Config};
     Config::new().with_byte_suffix(ByteSuffix::Deny).parse_size(input)
 }
 
+impl RowArgs {
+    /// Computes the number of rows of the file with the given (1-based) index.
+    fn file_rows_count(&self, file_index: u32) -> u64 {
+        if file_index == self.files_count {
+            self.total_count - u64::from(self.files_count - 1) * self.rows_per_file
+        } else {
+            self.rows_per_file
+        }
+    }
+}
+
 impl Args {
     /// Computes the row-related arguments.
-    fn row_args(&self) -> RowArgs {
+    pub(crate) fn row_args(&self) -> RowArgs {
         let mut res = RowArgs {
             rows_count: self.rows_count,
             ..RowArgs::default()
@@ -376,6 +640,17 @@ impl Args {
 
         res
     }
+
+    /// Obtains the page compression codec of Parquet files from `--compression`.
+    #[cfg(feature = "parquet")]
+    pub(crate) fn parquet_compression(&self) -> Result<parquet::basic::Compression, S<Error>> {
+        match self.compression {
+            Some(compression) if self.format == FormatName::Parquet => {
+                compression.parquet_compression(self.compress_level)
+            }
+            _ => Ok(parquet::basic::Compression::UNCOMPRESSED),
+        }
+    }
 }
 
 fn now_from_str(s: &str) -> ParseResult<NaiveDateTime> {
@@ -383,7 +658,7 @@ fn now_from_str(s: &str) -> ParseResult<NaiveDateTime> {
 }
 
 /// Extension trait for `Result` to annotate it with a file path.
-trait PathResultExt {
+pub(crate) trait PathResultExt {
     type Ok;
     fn with_path(self, action: &'static str, path: &Path) -> Result<Self::Ok, S<Error>>;
     fn with_path_fn(self, action: &'static str, path_fn: impl FnOnce() -> PathBuf) -> Result<Self::Ok, S<Error>>;
@@ -426,12 +701,500 @@ fn read_template_file(path: &Path) -> Result<String, S<Error>> {
     .with_path("read template", path)
 }
 
+/// Parses a `[TABLE=]COLUMN,...` argument into the table name (if any) and the column names.
+pub(crate) fn parse_table_columns(arg: &str) -> Result<(Option<QName>, Vec<String>), S<Error>> {
+    let (table, columns) = match arg.split_once('=') {
+        Some((table, columns)) => (Some(QName::parse(table).no_span_err()?), columns),
+        None => (None, arg),
+    };
+    let columns = columns
+        .split(',')
+        .map(str::trim)
+        .filter(|col| !col.is_empty())
+        .map(str::to_owned)
+        .collect();
+    Ok((table, columns))
+}
+
+/// Checks whether the table name given in a CLI argument refers to the table. Table names
+/// without a schema match tables of any schema.
+pub(crate) fn is_same_table(arg_name: &QName, table_name: &QName) -> bool {
+    if arg_name.schema_name().is_some() {
+        arg_name.unique_name() == table_name.unique_name()
+    } else {
+        table_name.unique_name().rsplit('.').next() == Some(arg_name.unique_name())
+    }
+}
+
+/// Returns the index of the root table of every table.
+pub(crate) fn root_table_indices(tables: &[Table]) -> Vec<usize> {
+    let mut roots = (0..tables.len()).collect::<Vec<_>>();
+    // Derived tables always come after their parents.
+    for (index, table) in tables.iter().enumerate() {
+        for (child, _) in &table.derived {
+            roots[*child] = roots[index];
+        }
+    }
+    roots
+}
+
+/// Evaluates the global expressions, then sets the row counts of the root tables declared in the
+/// template or given by `--table-rows`.
+pub(crate) fn eval_globals(
+    args: &Args,
+    tables: &mut [Table],
+    mut ctx: CompileContext,
+    global_exprs: Vec<S<Expr>>,
+    rows_counts: Vec<(usize, S<Expr>)>,
+    seeding_rng: &mut rand_hc::Hc128Rng,
+) -> Result<CompileContext, S<Error>> {
+    if !global_exprs.is_empty() || !rows_counts.is_empty() {
+        let globals_count = global_exprs.len();
+        let (indices, counts): (Vec<_>, Vec<_>) = rows_counts.into_iter().unzip();
+        let spans = counts.iter().map(|count| count.span).collect::<Vec<_>>();
+        let row_gen = ctx.compile_row(global_exprs.into_iter().chain(counts).collect())?;
+        let mut state = State::new(0, args.rng.create(seeding_rng), ctx);
+        let values = row_gen.eval(&mut state)?;
+        for ((index, span), value) in indices
+            .into_iter()
+            .zip(spans)
+            .zip(values.into_iter().skip(globals_count))
+        {
+            tables[index].rows_count = Some(value.try_into().span_err(span)?);
+        }
+        ctx = state.into_compile_context();
+    }
+
+    let roots = root_table_indices(tables);
+    for arg in &args.table_rows {
+        let invalid =
+            || Error::InvalidArguments(format!("invalid --table-rows argument {arg}, expected TABLE=N")).no_span();
+        let (name, count) = arg.split_once('=').ok_or_else(invalid)?;
+        let name = QName::parse(name.trim()).no_span_err()?;
+        let count = parse_row_count(count.trim()).map_err(|_| invalid())?;
+        let mut found = false;
+        for (index, table) in tables.iter_mut().enumerate() {
+            if !is_same_table(&name, &table.name) {
+                continue;
+            }
+            if roots[index] != index {
+                return Err(Error::InvalidArguments(format!(
+                    "cannot set the row count of the derived table {}",
+                    table.name.table_name(true)
+                ))
+                .no_span());
+            }
+            table.rows_count = Some(count);
+            found = true;
+        }
+        if !found {
+            return Err(Error::InvalidArguments(format!("cannot find table {}", name.table_name(true))).no_span());
+        }
+    }
+
+    // The rows of every root table are generated for the `rownum` of the main table.
+    let total_count = args.row_args().total_count;
+    for table in tables.iter() {
+        if let Some(rows_count) = table
+            .rows_count
+            .filter(|rows_count| !args.stream && *rows_count > total_count)
+        {
+            return Err(Error::InvalidArguments(format!(
+                "table {} cannot have {rows_count} rows, which is more than the total count {total_count}",
+                table.name.table_name(true)
+            ))
+            .no_span());
+        }
+    }
+    Ok(ctx)
+}
+
+/// Builds the CSV format options from the CLI arguments.
+fn csv_options(args: &Args) -> Result<CsvOptions, S<Error>> {
+    let to_ascii = |c: char| {
+        u8::try_from(c).ok().filter(u8::is_ascii).ok_or_else(|| {
+            Error::UnsupportedCliParameter {
+                kind: "CSV character (must be ASCII)",
+                value: c.to_string(),
+            }
+            .no_span()
+        })
+    };
+    let default = CsvOptions::default();
+    let options = CsvOptions {
+        delimiter: args.csv_delimiter.map_or(Ok(default.delimiter), to_ascii)?,
+        quote: args.csv_quote.map_or(Ok(default.quote), to_ascii)?,
+        escape: args.csv_escape.map(to_ascii).transpose()?,
+        quote_policy: args.csv_quote_policy.unwrap_or(default.quote_policy),
+        line_terminator: args.csv_line_terminator.unwrap_or(default.line_terminator),
+        bom: args.csv_bom,
+    };
+    // the records could not be parsed back if these characters were ambiguous.
+    let message = if [options.delimiter, options.quote]
+        .iter()
+        .any(|c| [b'\r', b'\n'].contains(c))
+    {
+        "--csv-delimiter and --csv-quote cannot be a line break"
+    } else if options.delimiter == options.quote {
+        "--csv-delimiter and --csv-quote must be different"
+    } else if options.quote_policy == CsvQuotePolicy::Never && options.escape.is_none() {
+        "--csv-quote-policy never requires --csv-escape"
+    } else {
+        return Ok(options);
+    };
+    Err(Error::InvalidArguments(message.to_owned()).no_span())
+}
+
+/// Resolves the conflict clause of every table from the `--conflict-target` and
+/// `--update-columns` arguments. Arguments naming a table take precedence over those without.
+fn resolve_conflict_clauses(args: &Args, tables: &[Table]) -> Result<HashMap<String, ConflictClause>, S<Error>> {
+    let mode_name = args
+        .insert_mode
+        .to_possible_value()
+        .map_or_else(String::new, |v| v.get_name().to_owned());
+    match (args.insert_mode, args.dialect) {
+        (InsertMode::Insert, _) => return Ok(HashMap::new()),
+        // the upsert syntax differs between every dialect, so it should not be guessed.
+        (InsertMode::Upsert, None) => {
+            return Err(Error::InvalidArguments("--insert-mode upsert requires --dialect".to_owned()).no_span());
+        }
+        (_, Some(dialect @ (Dialect::SQLServer | Dialect::Oracle)))
+        | (InsertMode::Replace, Some(dialect @ Dialect::PostgreSQL)) => {
+            let dialect_name = dialect
+                .to_possible_value()
+                .map_or_else(String::new, |v| v.get_name().to_owned());
+            return Err(Error::InvalidArguments(format!(
+                "--insert-mode {mode_name} is not supported by the {dialect_name} dialect"
+            ))
+            .no_span());
+        }
+        _ => {}
+    }
+    if !args.format.is_sql_insert() {
+        return Err(Error::InvalidArguments(format!(
+            "--insert-mode {mode_name} is not supported by the {} format",
+            args.format.name()
+        ))
+        .no_span());
+    }
+
+    let conflict_targets = args
+        .conflict_target
+        .iter()
+        .map(|arg| parse_table_columns(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+    let update_columns = args
+        .update_columns
+        .iter()
+        .map(|arg| parse_table_columns(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+    for name in conflict_targets
+        .iter()
+        .chain(&update_columns)
+        .filter_map(|(name, _)| name.as_ref())
+    {
+        if !tables.iter().any(|table| is_same_table(name, &table.name)) {
+            return Err(Error::InvalidArguments(format!("cannot find table {}", name.table_name(true))).no_span());
+        }
+    }
+    let find_columns = |specs: &[(Option<QName>, Vec<String>)], table: &Table| {
+        specs
+            .iter()
+            .rev()
+            .find(|(name, _)| name.as_ref().is_some_and(|name| is_same_table(name, &table.name)))
+            .or_else(|| specs.iter().rev().find(|(name, _)| name.is_none()))
+            .map(|(_, columns)| columns.clone())
+    };
+
+    let requires_target =
+        args.insert_mode == InsertMode::Upsert && matches!(args.dialect, Some(Dialect::PostgreSQL | Dialect::SQLite));
+    let mut clauses = HashMap::with_capacity(tables.len());
+    for table in tables {
+        let target = find_columns(&conflict_targets, table).unwrap_or_default();
+        if requires_target && target.is_empty() {
+            return Err(Error::InvalidArguments(format!(
+                "--insert-mode upsert requires --conflict-target for table {} in this SQL dialect",
+                table.name.table_name(true)
+            ))
+            .no_span());
+        }
+        clauses.insert(
+            table.name.table_name(args.qualified).to_owned(),
+            ConflictClause {
+                target,
+                update_columns: find_columns(&update_columns, table),
+            },
+        );
+    }
+    Ok(clauses)
+}
+
+/// A destination of the rows which only supports some of the arguments.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum OutputKind {
+    /// Files in a local output directory.
+    Directory,
+    /// A single stream, i.e. the standard output or a TCP connection.
+    Stream,
+    /// Objects in an S3 bucket.
+    S3,
+    /// A PostgreSQL or MySQL database loaded directly.
+    Database,
+    /// A SQLite database file written in the sqlite format.
+    Sqlite,
+}
+
+/// An argument which cannot be used with every kind of output.
+struct RestrictedArg {
+    /// The command line flag.
+    flag: &'static str,
+    /// Whether the argument is given.
+    is_given: fn(&Args) -> bool,
+    /// The kinds of output which cannot be used with the argument.
+    rejected_by: &'static [OutputKind],
+}
+
+/// The arguments which cannot be used with every kind of output.
+const RESTRICTED_ARGS: &[RestrictedArg] = {
+    use OutputKind::{Database, Directory, S3, Sqlite, Stream};
+    &[
+        RestrictedArg {
+            flag: "--compression",
+            is_given: |args| args.compression.is_some(),
+            rejected_by: &[Stream, Database, Sqlite],
+        },
+        RestrictedArg {
+            flag: "--compressed-size",
+            is_given: |args| args.compressed_size.is_some(),
+            rejected_by: &[Stream, Database, Sqlite],
+        },
+        RestrictedArg {
+            flag: "--transaction-size",
+            is_given: |args| args.transaction_size.is_some(),
+            rejected_by: &[Database],
+        },
+        RestrictedArg {
+            flag: "--manifest",
+            is_given: |args| args.manifest,
+            rejected_by: &[Stream, Database, Sqlite],
+        },
+        RestrictedArg {
+            flag: "--resume",
+            is_given: |args| args.resume,
+            rejected_by: &[Stream, S3, Database, Sqlite],
+        },
+        RestrictedArg {
+            flag: "--clean-temp-files",
+            is_given: |args| args.clean_temp_files,
+            rejected_by: &[Stream, S3, Database],
+        },
+        RestrictedArg {
+            flag: "--partition-by",
+            is_given: |args| !args.partition_by.is_empty(),
+            rejected_by: &[Stream, Database, Sqlite],
+        },
+        RestrictedArg {
+            flag: "--preamble",
+            is_given: |args| args.preamble.is_some(),
+            rejected_by: &[Sqlite],
+        },
+        RestrictedArg {
+            flag: "--postamble",
+            is_given: |args| args.postamble.is_some(),
+            rejected_by: &[Sqlite],
+        },
+        RestrictedArg {
+            flag: "--stream",
+            is_given: |args| args.stream,
+            rejected_by: &[Directory, S3, Database],
+        },
+    ]
+};
+
+/// Checks if the arguments can be used with the kind of output, described by `context` in the
+/// error message, e.g. `when writing to S3`.
+fn validate_output_args(args: &Args, kind: OutputKind, context: &str) -> Result<(), S<Error>> {
+    match RESTRICTED_ARGS
+        .iter()
+        .find(|arg| arg.rejected_by.contains(&kind) && (arg.is_given)(args))
+    {
+        Some(arg) => Err(Error::InvalidArguments(format!("{} cannot be used {context}", arg.flag)).no_span()),
+        None => Ok(()),
+    }
+}
+
+/// Checks if the arguments can be used when writing to the standard output, or any other single
+/// `output` stream.
+fn validate_stdout_args(args: &Args, tables_count: usize, components_mask: u8, output: &str) -> Result<(), S<Error>> {
+    let format_name = args.format.name();
+    if !args.format.is_textual() {
+        return Err(
+            Error::InvalidArguments(format!("the {format_name} format cannot be written to {output}")).no_span(),
+        );
+    }
+    validate_output_args(args, OutputKind::Stream, &format!("when writing to {output}"))?;
+    let message = if args.format.is_sql_insert() {
+        return Ok(());
+    } else if tables_count > 1 {
+        format!("a template with multiple tables cannot be written to {output} in the {format_name} format")
+    } else if ComponentName::Schema.is_in(components_mask) || ComponentName::Table.is_in(components_mask) {
+        format!("only the data component can be written to {output} in the {format_name} format")
+    } else {
+        return Ok(());
+    };
+    Err(Error::InvalidArguments(message).no_span())
+}
+
+/// Prepares the destination of the data files which are not loaded into a database.
+///
+/// Returns whether the files are concatenated into a single output (the standard output or a TCP
+/// connection), and the TCP connection if any. Otherwise the output directory is created.
+fn open_output(
+    args: &Args,
+    tables_count: usize,
+    components_mask: u8,
+    database_kind: Option<DatabaseKind>,
+) -> Result<(bool, Option<TcpStream>), S<Error>> {
+    let tcp_address = args.out_dir.to_str().and_then(|out_dir| out_dir.strip_prefix("tcp://"));
+    let stdout = args.out_dir == Path::new("-") || tcp_address.is_some();
+    if stdout {
+        let output = if tcp_address.is_some() {
+            "a TCP connection"
+        } else {
+            "the standard output"
+        };
+        validate_stdout_args(args, tables_count, components_mask, output)?;
+    } else if is_s3_url(&args.out_dir) {
+        validate_s3_args(args)?;
+    } else if database_kind.is_none() {
+        validate_output_args(args, OutputKind::Directory, "when writing to an output directory")?;
+        create_dir_all(&args.out_dir).with_path("create output directory", &args.out_dir)?;
+    }
+    if args.rows_per_second == Some(0) || args.bytes_per_second == Some(0) {
+        return Err(
+            Error::InvalidArguments("--rows-per-second and --bytes-per-second must be positive".to_owned()).no_span(),
+        );
+    }
+    let tcp = tcp_address
+        .map(|address| TcpStream::connect(address).with_path("connect to TCP address", &args.out_dir))
+        .transpose()?;
+    Ok((stdout, tcp))
+}
+
+/// Returns whether the output directory is an `s3://` URL.
+fn is_s3_url(out_dir: &Path) -> bool {
+    out_dir.to_str().is_some_and(|url| url.starts_with("s3://"))
+}
+
+/// Checks if the arguments can be used when uploading into an S3 bucket.
+fn validate_s3_args(args: &Args) -> Result<(), S<Error>> {
+    #[cfg(feature = "sqlite")]
+    if args.format == FormatName::Sqlite {
+        return Err(Error::InvalidArguments("the sqlite format cannot be written to S3".to_owned()).no_span());
+    }
+    if cfg!(not(feature = "s3")) {
+        return Err(Error::InvalidArguments(
+            "writing to S3 requires dbgen to be built with the `s3` feature".to_owned(),
+        )
+        .no_span());
+    }
+    validate_output_args(args, OutputKind::S3, "when writing to S3")
+}
+
+/// Opens the S3 bucket receiving all files, if the output directory is an `s3://` URL.
+#[cfg(feature = "s3")]
+fn open_s3_bucket(args: &Args) -> Result<Option<Arc<S3Bucket>>, S<Error>> {
+    let Some(url) = args.out_dir.to_str().filter(|url| url.starts_with("s3://")) else {
+        return Ok(None);
+    };
+    let bucket = S3Bucket::connect(url, args.size.or(args.compressed_size));
+    Ok(Some(Arc::new(bucket.no_span_err()?)))
+}
+
+/// A database which the rows are loaded into directly.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum DatabaseKind {
+    /// PostgreSQL, loaded through `COPY`.
+    Postgres,
+    /// MySQL or `TiDB`, loaded through INSERT or `LOAD DATA` statements.
+    MySql,
+}
+
+impl DatabaseKind {
+    /// Returns the kind of database if the output directory is a database URL.
+    fn from_out_dir(out_dir: &Path) -> Option<Self> {
+        let url = out_dir.to_str()?;
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            Some(Self::Postgres)
+        } else if url.starts_with("mysql://") {
+            Some(Self::MySql)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the display name of the database.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Postgres => "PostgreSQL",
+            Self::MySql => "MySQL",
+        }
+    }
+}
+
+/// Checks if the arguments can be used when loading into a database, and fills in the dialect.
+fn validate_database_args(args: &mut Args, kind: DatabaseKind) -> Result<(), S<Error>> {
+    let name = kind.name();
+    let message = match kind {
+        DatabaseKind::Postgres if cfg!(not(feature = "postgres")) => {
+            format!("loading into {name} requires dbgen to be built with the `postgres` feature")
+        }
+        DatabaseKind::MySql if cfg!(not(feature = "mysql")) => {
+            format!("loading into {name} requires dbgen to be built with the `mysql` feature")
+        }
+        DatabaseKind::Postgres if args.format != FormatName::PgCopy => {
+            format!("loading into {name} requires the pg-copy format")
+        }
+        DatabaseKind::MySql
+            if !matches!(
+                args.format,
+                FormatName::Sql | FormatName::SqlInsertSet | FormatName::MySqlTsv
+            ) =>
+        {
+            format!("loading into {name} requires the sql, sql-insert-set or mysql-tsv format")
+        }
+        DatabaseKind::MySql if !matches!(args.dialect, None | Some(Dialect::MySQL)) => {
+            format!("loading into {name} requires --dialect mysql")
+        }
+        _ if args.headers && args.format != FormatName::Sql && args.format != FormatName::SqlInsertSet => {
+            format!(
+                "--headers cannot be used with the {} format when loading into {name}",
+                args.format.name()
+            )
+        }
+        _ => {
+            validate_output_args(args, OutputKind::Database, &format!("when loading into {name}"))?;
+            if kind == DatabaseKind::MySql {
+                args.dialect = Some(Dialect::MySQL);
+            }
+            return Ok(());
+        }
+    };
+    Err(Error::InvalidArguments(message).no_span())
+}
+
 /// Runs the CLI program.
 // ALLOW_REASON: we will try to refactor this some day...
 #[allow(clippy::too_many_lines)]
-pub fn run(args: Args, span_registry: &mut Registry) -> Result<(), S<Error>> {
+pub fn run(mut args: Args, span_registry: &mut Registry) -> Result<(), S<Error>> {
     let row_args = args.row_args();
-    let input = match (args.template_string, &args.template) {
+    let manifest_args = args
+        .manifest
+        .then(|| serde_json::to_value(&args).expect("arguments should be serializable"));
+    let checkpoint_args = args
+        .resume
+        .then(|| serde_json::to_value(&args).expect("arguments should be serializable"));
+    let input = match (args.template_string.take(), &args.template) {
         (Some(input), _) => input,
         (None, Some(template)) => read_template_file(template)?,
         _ => {
@@ -455,16 +1218,73 @@ pub fn run(args: Args, span_registry: &mut Registry) -> Result<(), S<Error>> {
 
     let mut ctx = CompileContext::new(template.variables_count);
     ctx.current_timestamp = args.now.unwrap_or_else(|| Utc::now().naive_utc());
-    let tables = template
+    let mut tables = template
         .tables
         .into_iter()
         .map(|t| ctx.compile_table(t))
-        .collect::<Result<_, _>>()?;
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let database_kind = DatabaseKind::from_out_dir(&args.out_dir);
+    if let Some(kind) = database_kind {
+        validate_database_args(&mut args, kind)?;
+    } else if args.batch_size.is_some() || args.max_retries.is_some() || args.session_init.is_some() {
+        return Err(Error::InvalidArguments(
+            "--batch-size, --max-retries and --session-init can only be used when loading into a database".to_owned(),
+        )
+        .no_span());
+    }
 
-    create_dir_all(&args.out_dir).with_path("create output directory", &args.out_dir)?;
+    #[cfg(feature = "sqlite")]
+    if args.format == FormatName::Sqlite {
+        match args.dialect {
+            None | Some(Dialect::SQLite) => args.dialect = Some(Dialect::SQLite),
+            Some(_) => {
+                return Err(
+                    Error::InvalidArguments("the sqlite format requires --dialect sqlite".to_owned()).no_span(),
+                );
+            }
+        }
+        validate_output_args(&args, OutputKind::Sqlite, "with the sqlite format")?;
+    }
+
+    let conflict_clauses = resolve_conflict_clauses(&args, &tables)?;
+    if args.dialect == Some(Dialect::SQLServer) && args.format == FormatName::Sql && args.rows_count > 1000 {
+        return Err(Error::InvalidArguments(
+            "the sqlserver dialect accepts at most 1000 rows per INSERT statement (-r)".to_owned(),
+        )
+        .no_span());
+    }
+    if args.format == FormatName::MySqlTsv && args.compression.is_some() {
+        return Err(Error::InvalidArguments(
+            "--compression cannot be used with the mysql-tsv format, as LOAD DATA cannot read compressed files"
+                .to_owned(),
+        )
+        .no_span());
+    }
+    let partitionings = resolve_partitionings(&args, &tables)?;
+    let csv_options = csv_options(&args)?;
+    if (args.transaction_size.is_some() || args.preamble.is_some() || args.postamble.is_some())
+        && !args.format.is_sql_insert()
+    {
+        return Err(Error::InvalidArguments(format!(
+            "--transaction-size, --preamble and --postamble are not supported by the {} format",
+            args.format.name()
+        ))
+        .no_span());
+    }
 
     let compress_level = args.compress_level;
-    let mut components_mask = ComponentName::union_all(args.components);
+    if matches!(args.compression, Some(CompressionName::Brotli)) && args.compress_threads != 1 {
+        return Err(Error::InvalidArguments(
+            "--compress-threads cannot be used with brotli, which is always compressed on one thread".to_owned(),
+        )
+        .no_span());
+    }
+    let compress_threads = match args.compress_threads {
+        0 => available_parallelism().map_or(1, |n| u32::try_from(n.get()).unwrap_or(u32::MAX)),
+        n => n,
+    };
+    let mut components_mask = ComponentName::union_all(mem::take(&mut args.components));
     if args.no_data {
         ComponentName::Data.remove_from(&mut components_mask);
     }
@@ -473,29 +1293,169 @@ pub fn run(args: Args, span_registry: &mut Registry) -> Result<(), S<Error>> {
         ComponentName::Table.remove_from(&mut components_mask);
     }
     let format = args.format;
+    let compression = args.compression.map(|c| (c, compress_level));
+    #[cfg(feature = "parquet")]
+    let parquet_compression = args.parquet_compression()?;
+    let format_options = Options {
+        escape_backslash: args.escape_backslash,
+        headers: args.headers,
+        true_string: args
+            .format_true
+            .take()
+            .map_or_else(|| format.default_true_string(args.dialect), Cow::Owned),
+        false_string: args
+            .format_false
+            .take()
+            .map_or_else(|| format.default_false_string(args.dialect), Cow::Owned),
+        null_string: args
+            .format_null
+            .take()
+            .map_or_else(|| format.default_null_string(), Cow::Owned),
+        dialect: args.dialect,
+        insert_mode: args.insert_mode,
+        conflict_clauses,
+        transaction_size: args.transaction_size,
+        preamble: args.preamble.take().map_or(Cow::Borrowed(""), Cow::Owned),
+        postamble: args.postamble.take().map_or(Cow::Borrowed(""), Cow::Owned),
+        csv: csv_options,
+    };
+    if args.estimate {
+        return print_estimate(
+            &args,
+            &mut tables,
+            ctx,
+            template.global_exprs,
+            template.rows_counts,
+            &format_options,
+        );
+    }
+    let (stdout, tcp) = open_output(&args, tables.len(), components_mask, database_kind)?;
+    #[cfg(feature = "s3")]
+    let s3 = open_s3_bucket(&args)?;
+    if args.clean_temp_files {
+        remove_temp_files(&args.out_dir, &tables)?;
+    }
+    let checkpoint = checkpoint_args
+        .map(|checkpoint_args| Checkpoint::open(&args.out_dir, args.seed, checkpoint_args, &input))
+        .transpose()?;
+    let meta_seed = checkpoint.as_ref().map_or_else(
+        || args.seed.unwrap_or_else(|| OsRng.r#gen()),
+        |checkpoint| checkpoint.seed,
+    );
+    let mut seeding_rng = meta_seed.make_rng();
+    let rng_name = args.rng;
+    let ctx = eval_globals(
+        &args,
+        &mut tables,
+        ctx,
+        template.global_exprs,
+        template.rows_counts,
+        &mut seeding_rng,
+    )?;
+    #[cfg(feature = "sqlite")]
+    let database = if format == FormatName::Sqlite {
+        let path = args
+            .out_dir
+            .join(args.sqlite_file.as_deref().unwrap_or(Path::new("data.db")));
+        // Creating the tables means starting from an empty database.
+        if ComponentName::Table.is_in(components_mask) && path.exists() {
+            if !args.sqlite_overwrite {
+                return Err(Error::InvalidArguments(format!(
+                    "the SQLite database {} already exists, use --sqlite-overwrite to replace it",
+                    path.display()
+                ))
+                .no_span());
+            }
+            std::fs::remove_file(&path).with_path("remove database file", &path)?;
+        }
+        Some(SqliteDatabase::open(&path).no_span_err()?)
+    } else {
+        None
+    };
+    #[cfg(any(feature = "postgres", feature = "mysql"))]
+    let (database_url, max_retries) = (args.out_dir.to_str().unwrap_or_default(), args.max_retries.unwrap_or(3));
+    #[cfg(feature = "postgres")]
+    let postgres = if database_kind == Some(DatabaseKind::Postgres) {
+        let table_names = tables.iter().map(|table| table.name.table_name(args.qualified));
+        let database = PostgresDatabase::connect(
+            database_url,
+            pool.current_num_threads(),
+            table_names,
+            &format_options.null_string,
+            args.session_init.clone(),
+            max_retries,
+        );
+        Some(Arc::new(database.no_span_err()?))
+    } else {
+        None
+    };
+    #[cfg(feature = "mysql")]
+    let mysql = if database_kind == Some(DatabaseKind::MySql) {
+        let targets = tables.iter().map(|table| {
+            let name = table.name.table_name(args.qualified);
+            let load_data_statement = (format == FormatName::MySqlTsv).then(|| {
+                let mut statement = Vec::new();
+                MySqlTsvFormat(&format_options)
+                    .write_load_data_statement(&mut statement, &table.schema(args.qualified), name)
+                    .expect("writing into a Vec should not fail");
+                String::from_utf8_lossy(&statement).into_owned()
+            });
+            (name.to_owned(), load_data_statement)
+        });
+        let database = MySqlDatabase::connect(
+            database_url,
+            pool.current_num_threads(),
+            targets,
+            args.session_init.clone(),
+            max_retries,
+        );
+        Some(Arc::new(database.no_span_err()?))
+    } else {
+        None
+    };
     let env = Env {
         out_dir: args.out_dir,
         file_num_digits: args.files_count.to_string().len(),
+        max_row_nums: {
+            let roots = root_table_indices(&tables);
+            roots.into_iter().map(|root| tables[root].rows_count).collect()
+        },
         tables,
         qualified: args.qualified,
         rows_count: args.rows_count,
         format,
-        format_options: Options {
-            escape_backslash: args.escape_backslash,
-            headers: args.headers,
-            true_string: args
-                .format_true
-                .map_or_else(|| format.default_true_string(), Cow::Owned),
-            false_string: args
-                .format_false
-                .map_or_else(|| format.default_false_string(), Cow::Owned),
-            null_string: args
-                .format_null
-                .map_or_else(|| format.default_null_string(), Cow::Owned),
-        },
-        compression: args.compression.map(|c| (c, compress_level)),
+        format_options,
+        compression: compression.filter(|_| !format.compresses_pages()),
+        compress_threads,
+        #[cfg(feature = "parquet")]
+        parquet_compression,
         components_mask,
-        file_size: args.size,
+        file_size: args
+            .size
+            .map(TargetSize::Uncompressed)
+            .or_else(|| args.compressed_size.map(TargetSize::Compressed)),
+        #[cfg(feature = "sqlite")]
+        database,
+        #[cfg(feature = "postgres")]
+        postgres,
+        #[cfg(feature = "mysql")]
+        mysql,
+        #[cfg(feature = "s3")]
+        s3,
+        stdout,
+        tcp,
+        flush_interval: if stdout {
+            NonZeroU64::new(1)
+        } else if database_kind.is_some() {
+            Some(args.batch_size.unwrap_or(NonZeroU64::MIN))
+        } else {
+            None
+        },
+        manifest: args.manifest,
+        checkpoint,
+        partitionings,
+        max_open_partitions: args.max_open_partitions.unwrap_or(DEFAULT_MAX_OPEN_PARTITIONS),
+        rate_limit: RateLimit::new(args.rows_per_second, args.bytes_per_second),
     };
 
     if ComponentName::Schema.is_in(env.components_mask) {
@@ -504,67 +1464,128 @@ pub fn run(args: Args, span_registry: &mut Registry) -> Result<(), S<Error>> {
     if ComponentName::Table.is_in(env.components_mask) {
         env.write_table_schema()?;
     }
+    #[cfg(feature = "sqlite")]
+    if env.database.is_some() && !ComponentName::Data.is_in(env.components_mask) {
+        return Ok(());
+    }
+    if database_kind.is_some() && !ComponentName::Data.is_in(env.components_mask) {
+        return Ok(());
+    }
 
-    let meta_seed = args.seed.unwrap_or_else(|| OsRng.r#gen());
     let show_progress = !args.quiet;
     if show_progress {
-        println!("Using seed: {meta_seed}");
+        if stdout {
+            eprintln!("Using seed: {meta_seed}");
+        } else {
+            println!("Using seed: {meta_seed}");
+        }
     }
-    let mut seeding_rng = meta_seed.make_rng();
 
-    let rng_name = args.rng;
-
-    // Evaluate the global expressions if necessary.
-    if !template.global_exprs.is_empty() {
-        let row_gen = ctx.compile_row(template.global_exprs)?;
-        let mut state = State::new(0, rng_name.create(&mut seeding_rng), ctx);
-        row_gen.eval(&mut state)?;
-        ctx = state.into_compile_context();
-    }
+    // In per-row mode, the RNG of every row is derived from this seed instead of the file's seed.
+    let row_seed = args.per_row_rng.then(|| seeding_rng.r#gen::<[u8; 32]>());
+    let new_state = |row_num, rng: Box<dyn RngCore + Send>| {
+        let mut state = State::new(row_num, rng, ctx.clone());
+        if let Some(row_seed) = row_seed {
+            state.seed_per_row(row_seed);
+        }
+        state
+    };
 
     WRITE_FINISHED.store(false, Ordering::Relaxed);
     WRITE_PROGRESS.store(0, Ordering::Relaxed);
     WRITTEN_SIZE.store(0, Ordering::Relaxed);
 
-    let progress_bar_thread = spawn(move || {
-        if show_progress {
-            run_progress_thread(row_args.total_count);
-        }
-    });
-
-    let iv = (0..row_args.files_count)
-        .map(move |i| {
-            let file_index = i + 1;
+    let file_range = args.file_range;
+    if let Some(range) = file_range {
+        range.validate(row_args.files_count)?;
+    }
+    let (files, total_count): (Box<dyn Iterator<Item = FileTask> + Send>, u64) = if args.stream {
+        let files = (0..).map(move |i: u64| {
             (
                 rng_name.create(&mut seeding_rng),
                 FileInfo {
-                    file_index,
-                    inserts_count: if file_index == row_args.files_count {
-                        row_args.last_file_inserts_count
-                    } else {
-                        row_args.inserts_count
-                    },
-                    last_insert_rows_count: if file_index == row_args.files_count {
-                        row_args.last_file_final_insert_rows_count
-                    } else {
-                        row_args.final_insert_rows_count
-                    },
+                    // The file index only names the data files, which are not written when streaming.
+                    file_index: u32::try_from(i + 1).unwrap_or(u32::MAX),
+                    inserts_count: row_args.inserts_count,
+                    last_insert_rows_count: row_args.final_insert_rows_count,
+                    stdout_sender: None,
                 },
-                u64::from(i) * row_args.rows_per_file + 1,
+                i * row_args.rows_per_file + 1,
             )
-        })
-        .collect::<Vec<_>>();
-    let res = pool.install(move || {
-        iv.into_par_iter().try_for_each(|(seed, file_info, row_num)| {
-            let mut state = State::new(row_num, seed, ctx.clone());
-            env.write_data_file(&file_info, &mut state)
-        })
+        });
+        (Box::new(files), 0)
+    } else {
+        let iv = (0..row_args.files_count)
+            .map(move |i| {
+                let file_index = i + 1;
+                (
+                    rng_name.create(&mut seeding_rng),
+                    FileInfo {
+                        file_index,
+                        inserts_count: if file_index == row_args.files_count {
+                            row_args.last_file_inserts_count
+                        } else {
+                            row_args.inserts_count
+                        },
+                        last_insert_rows_count: if file_index == row_args.files_count {
+                            row_args.last_file_final_insert_rows_count
+                        } else {
+                            row_args.final_insert_rows_count
+                        },
+                        stdout_sender: None,
+                    },
+                    u64::from(i) * row_args.rows_per_file + 1,
+                )
+            })
+            // The seeds of the skipped files are still derived, so the selected files are identical
+            // to those of a full run.
+            .filter(|(_, file_info, _)| {
+                file_range.is_none_or(|range| range.contains(file_info.file_index))
+                    && env
+                        .checkpoint
+                        .as_ref()
+                        .is_none_or(|checkpoint| !checkpoint.completed.contains_key(&file_info.file_index))
+            })
+            .collect::<Vec<_>>();
+        let total_count = iv
+            .iter()
+            .map(|(_, file_info, _)| row_args.file_rows_count(file_info.file_index))
+            .sum();
+        (Box::new(iv.into_iter()), total_count)
+    };
+
+    // The progress bar is shown on stderr when the data are written to stdout. There is no
+    // progress to show when streaming.
+    let progress_bar_thread = spawn(move || match (show_progress && !args.stream, stdout) {
+        (false, _) => {}
+        (true, false) => run_progress_thread(MultiBar::new(), total_count),
+        (true, true) => run_progress_thread(MultiBar::on(io::stderr()), total_count),
     });
 
+    let res = if stdout {
+        env.write_data_files_to_output(&pool, files, &new_state)
+            .map(|()| Vec::new())
+    } else {
+        pool.install(|| {
+            files
+                .collect::<Vec<_>>()
+                .into_par_iter()
+                .map(|(seed, file_info, row_num)| {
+                    let mut state = new_state(row_num, seed);
+                    env.write_data_file(&file_info, &mut state)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+    };
+
     WRITE_FINISHED.store(true, Ordering::Relaxed);
     progress_bar_thread.join().unwrap();
 
-    res?;
+    let written_files: Vec<Vec<WrittenFile>> = res?;
+    env.write_file_listings(&written_files, manifest_args.map(|args| (args, meta_seed, rng_name)))?;
+    if show_progress {
+        env.print_rows_counts();
+    }
     Ok(())
 }
 
@@ -572,7 +1593,7 @@ pub fn run(args: Args, span_registry: &mut Registry) -> Result<(), S<Error>> {
 ///
 /// This is represented as a 64-digit hex string and is supposed to seed the
 /// HC-128 RNG only.
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Seed(<rand_hc::Hc128Rng as SeedableRng>::Seed);
 
 impl FromStr for Seed {
@@ -627,6 +1648,86 @@ impl Seed {
     }
 }
 
+/// A range of file indices, written as `START..END`, `START..=END` or `START..`.
+///
+/// File indices start from 1, and a missing `START` means 1.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FileRange {
+    /// The first file index.
+    start: u32,
+    /// One past the last file index, or `None` if unbounded.
+    end: Option<u32>,
+}
+
+impl FileRange {
+    /// Checks whether the file index is in this range.
+    fn contains(self, file_index: u32) -> bool {
+        self.start <= file_index && self.end.is_none_or(|end| file_index < end)
+    }
+
+    /// Checks whether this range contains any of the files.
+    fn validate(self, files_count: u32) -> Result<(), S<Error>> {
+        if self.start > files_count || self.end.is_some_and(|end| end <= self.start) {
+            return Err(Error::InvalidArguments(format!(
+                "--file-range {self} does not contain any of the {files_count} files"
+            ))
+            .no_span());
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FileRange {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::UnsupportedCliParameter {
+            kind: "file range",
+            value: s.to_owned(),
+        };
+        let (start, end) = s.split_once("..").ok_or_else(invalid)?;
+        let start = if start.is_empty() {
+            1
+        } else {
+            start.parse().map_err(|_| invalid())?
+        };
+        let end = if let Some(end) = end.strip_prefix('=') {
+            let end: u32 = end.parse().map_err(|_| invalid())?;
+            Some(end.checked_add(1).ok_or_else(invalid)?)
+        } else if end.is_empty() {
+            None
+        } else {
+            Some(end.parse().map_err(|_| invalid())?)
+        };
+        if start == 0 {
+            return Err(invalid());
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl fmt::Display for FileRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..", self.start)?;
+        if let Some(end) = self.end {
+            write!(f, "{end}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for FileRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for FileRange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Names of random number generators supported by `dbgen`.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
 #[serde(rename_all = "lowercase")]
@@ -676,7 +1777,7 @@ impl FromStr for RngName {
 
 impl RngName {
     /// Creates an RNG engine given the name. The RNG engine instance will be seeded from `src`.
-    fn create(self, src: &mut rand_hc::Hc128Rng) -> Box<dyn RngCore + Send> {
+    pub(crate) fn create(self, src: &mut rand_hc::Hc128Rng) -> Box<dyn RngCore + Send> {
         match self {
             Self::ChaCha12 => Box::new(rand_chacha::ChaCha12Rng::from_seed(src.r#gen())),
             Self::ChaCha20 => Box::new(rand_chacha::ChaCha20Rng::from_seed(src.r#gen())),
@@ -700,6 +1801,24 @@ pub enum FormatName {
     Csv,
     /// SQL in INSERT-SET form
     SqlInsertSet,
+    /// JSON Lines (one JSON object per row)
+    #[serde(alias = "jsonl")]
+    #[value(alias = "jsonl")]
+    JsonLines,
+    /// Apache Parquet
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// PostgreSQL COPY (text format)
+    PgCopy,
+    /// PostgreSQL COPY (binary format)
+    PgCopyBinary,
+    /// MySQL LOAD DATA (tab-separated values)
+    #[serde(rename = "mysql-tsv")]
+    #[value(name = "mysql-tsv")]
+    MySqlTsv,
+    /// SQLite database
+    #[cfg(feature = "sqlite")]
+    Sqlite,
 }
 
 impl FromStr for FormatName {
@@ -709,6 +1828,14 @@ impl FromStr for FormatName {
             "sql" => Self::Sql,
             "csv" => Self::Csv,
             "sql-insert-set" => Self::SqlInsertSet,
+            "json-lines" | "jsonl" => Self::JsonLines,
+            #[cfg(feature = "parquet")]
+            "parquet" => Self::Parquet,
+            "pg-copy" => Self::PgCopy,
+            "pg-copy-binary" => Self::PgCopyBinary,
+            "mysql-tsv" => Self::MySqlTsv,
+            #[cfg(feature = "sqlite")]
+            "sqlite" => Self::Sqlite,
             _ => {
                 return Err(Error::UnsupportedCliParameter {
                     kind: "output format",
@@ -721,38 +1848,113 @@ impl FromStr for FormatName {
 
 impl FormatName {
     /// Obtains the file extension when using this format.
-    fn extension(self) -> &'static str {
+    pub(crate) fn extension(self) -> &'static str {
         match self {
             Self::Sql | Self::SqlInsertSet => "sql",
             Self::Csv => "csv",
+            Self::JsonLines => "jsonl",
+            #[cfg(feature = "parquet")]
+            Self::Parquet => "parquet",
+            Self::PgCopy => "copy",
+            Self::PgCopyBinary => "pgcopy",
+            Self::MySqlTsv => "tsv",
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite => "db",
         }
     }
 
-    /// Creates a formatter writer given the name.
-    fn create(self, options: &Options) -> Box<dyn Format + '_> {
+    /// Returns the name of this format as used on the command line.
+    fn name(self) -> String {
+        self.to_possible_value()
+            .map_or_else(String::new, |v| v.get_name().to_owned())
+    }
+
+    /// Whether the data are written by a stateless textual formatter.
+    fn is_textual(self) -> bool {
+        match self {
+            Self::PgCopyBinary => false,
+            #[cfg(feature = "parquet")]
+            Self::Parquet => false,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite => false,
+            _ => true,
+        }
+    }
+
+    /// Whether rows are inserted with SQL `INSERT` statements.
+    fn is_sql_insert(self) -> bool {
         match self {
+            Self::Sql | Self::SqlInsertSet => true,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the files are compressed page by page by the format itself instead of as a whole.
+    pub(crate) fn compresses_pages(self) -> bool {
+        match self {
+            #[cfg(feature = "parquet")]
+            Self::Parquet => true,
+            _ => false,
+        }
+    }
+
+    /// Creates a formatter writer given the name.
+    ///
+    /// Returns `None` for binary formats (Parquet and binary COPY) and databases which are not
+    /// written using a stateless formatter.
+    pub(crate) fn create(self, options: &Options) -> Option<Box<dyn Format + '_>> {
+        Some(match self {
             Self::Sql => Box::new(SqlFormat(options)),
             Self::Csv => Box::new(CsvFormat(options)),
             Self::SqlInsertSet => Box::new(SqlInsertSetFormat(options)),
-        }
+            Self::JsonLines => Box::new(JsonLinesFormat(options)),
+            Self::PgCopy => Box::new(PgCopyFormat(options)),
+            Self::MySqlTsv => Box::new(MySqlTsvFormat(options)),
+            Self::PgCopyBinary => return None,
+            #[cfg(feature = "parquet")]
+            Self::Parquet => return None,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite => return None,
+        })
     }
 
-    // ALLOW_REASON: future compatibility with other formats.
-    #[allow(clippy::unused_self)]
-    fn default_true_string(self) -> Cow<'static, str> {
-        Cow::Borrowed("1")
+    fn default_true_string(self, dialect: Option<Dialect>) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            Self::Sql | Self::SqlInsertSet if matches!(dialect, Some(Dialect::MySQL | Dialect::PostgreSQL)) => "TRUE",
+            Self::Sql | Self::SqlInsertSet | Self::Csv | Self::MySqlTsv => "1",
+            Self::JsonLines => "true",
+            Self::PgCopy | Self::PgCopyBinary => "t",
+            #[cfg(feature = "parquet")]
+            Self::Parquet => "1",
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite => "1",
+        })
     }
 
-    // ALLOW_REASON: future compatibility with other formats.
-    #[allow(clippy::unused_self)]
-    fn default_false_string(self) -> Cow<'static, str> {
-        Cow::Borrowed("0")
+    fn default_false_string(self, dialect: Option<Dialect>) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            Self::Sql | Self::SqlInsertSet if matches!(dialect, Some(Dialect::MySQL | Dialect::PostgreSQL)) => "FALSE",
+            Self::Sql | Self::SqlInsertSet | Self::Csv | Self::MySqlTsv => "0",
+            Self::JsonLines => "false",
+            Self::PgCopy | Self::PgCopyBinary => "f",
+            #[cfg(feature = "parquet")]
+            Self::Parquet => "0",
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite => "0",
+        })
     }
 
     fn default_null_string(self) -> Cow<'static, str> {
         Cow::Borrowed(match self {
             Self::Sql | Self::SqlInsertSet => "NULL",
-            Self::Csv => r"\N",
+            Self::Csv | Self::PgCopy | Self::PgCopyBinary | Self::MySqlTsv => r"\N",
+            Self::JsonLines => "null",
+            #[cfg(feature = "parquet")]
+            Self::Parquet => "NULL",
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite => "NULL",
         })
     }
 }
@@ -772,6 +1974,20 @@ pub enum CompressionName {
     #[serde(alias = "zst")]
     #[value(alias = "zst")]
     Zstd,
+    /// Compress as LZ4 frame format (`*.lz4`).
+    Lz4,
+    /// Compress as bzip2 format (`*.bz2`).
+    #[serde(alias = "bz2")]
+    #[value(alias = "bz2")]
+    Bzip2,
+    /// Compress as Brotli format (`*.br`).
+    #[serde(alias = "br")]
+    #[value(alias = "br")]
+    Brotli,
+    /// Compress as Snappy framed format (`*.sz`).
+    #[serde(alias = "snap")]
+    #[value(alias = "snap")]
+    Snappy,
 }
 
 impl FromStr for CompressionName {
@@ -781,6 +1997,10 @@ impl FromStr for CompressionName {
             "gzip" | "gz" => Self::Gzip,
             "xz" => Self::Xz,
             "zstd" | "zst" => Self::Zstd,
+            "lz4" => Self::Lz4,
+            "bzip2" | "bz2" => Self::Bzip2,
+            "brotli" | "br" => Self::Brotli,
+            "snappy" | "snap" => Self::Snappy,
             _ => {
                 return Err(Error::UnsupportedCliParameter {
                     kind: "compression format",
@@ -798,23 +2018,272 @@ impl CompressionName {
             Self::Gzip => "gz",
             Self::Xz => "xz",
             Self::Zstd => "zst",
+            Self::Lz4 => "lz4",
+            Self::Bzip2 => "bz2",
+            Self::Brotli => "br",
+            Self::Snappy => "sz",
         }
     }
 
-    /// Wraps a writer with a compression layer on top.
-    fn wrap<'a, W: Write + 'a>(self, inner: W, level: u8) -> Box<dyn Write + 'a> {
-        match self {
+    /// Wraps a writer with a compression layer on top, compressing on `threads_count` threads.
+    pub(crate) fn wrap<'a, W: Output + 'a>(
+        self,
+        inner: W,
+        level: u8,
+        threads_count: u32,
+    ) -> io::Result<Box<dyn Output + 'a>> {
+        if threads_count > 1 {
+            match self {
+                Self::Xz => {
+                    let stream = MtStreamBuilder::new()
+                        .threads(threads_count)
+                        .preset(level.into())
+                        .check(Check::Crc64)
+                        .encoder()?;
+                    return Ok(Box::new(XzEncoder::new_stream(inner, stream)));
+                }
+                Self::Zstd => {
+                    let mut encoder = zstd::Encoder::new(inner, level.into())?;
+                    encoder.multithread(threads_count)?;
+                    return Ok(Box::new(encoder));
+                }
+                // Concatenated streams of these formats are decompressed as a single stream.
+                Self::Gzip | Self::Lz4 | Self::Bzip2 | Self::Snappy => {
+                    return Ok(Box::new(ParallelCompressor::new(
+                        inner,
+                        threads_count as usize,
+                        move |input| {
+                            let mut output = Vec::new();
+                            let mut encoder = self.wrap(&mut output, level, 1)?;
+                            encoder.write_all(input)?;
+                            encoder.finish()?;
+                            drop(encoder);
+                            Ok(output)
+                        },
+                    )));
+                }
+                // Brotli streams cannot be concatenated, so it is always compressed on one thread.
+                Self::Brotli => {}
+            }
+        }
+        Ok(match self {
             Self::Gzip => Box::new(GzEncoder::new(inner, flate2::Compression::new(level.into()))),
             Self::Xz => Box::new(XzEncoder::new(inner, level.into())),
-            Self::Zstd => Box::new(
-                zstd::Encoder::new(inner, level.into())
-                    .expect("valid zstd encoder")
-                    .auto_finish(),
-            ),
+            Self::Zstd => Box::new(zstd::Encoder::new(inner, level.into())?),
+            Self::Lz4 => Box::new(Lz4Writer(Some(
+                lz4::EncoderBuilder::new().level(level.into()).build(inner)?,
+            ))),
+            Self::Bzip2 => Box::new(BzEncoder::new(inner, bzip2::Compression::new(level.clamp(1, 9).into()))),
+            Self::Brotli => Box::new(BrotliWriter(Some(brotli::CompressorWriter::new(
+                inner,
+                1 << 16,
+                level.min(11).into(),
+                22,
+            )))),
+            // Snappy has no compression levels.
+            Self::Snappy => Box::new(snap::write::FrameEncoder::new(inner)),
+        })
+    }
+
+    /// Obtains the page compression codec when writing Parquet files.
+    #[cfg(feature = "parquet")]
+    fn parquet_compression(self, level: u8) -> Result<parquet::basic::Compression, S<Error>> {
+        use parquet::basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel};
+        match self {
+            Self::Gzip => Ok(Compression::GZIP(GzipLevel::try_new(level.into()).no_span_err()?)),
+            Self::Zstd => Ok(Compression::ZSTD(ZstdLevel::try_new(level.into()).no_span_err()?)),
+            Self::Brotli => Ok(Compression::BROTLI(BrotliLevel::try_new(level.into()).no_span_err()?)),
+            Self::Lz4 => Ok(Compression::LZ4_RAW),
+            Self::Snappy => Ok(Compression::SNAPPY),
+            Self::Xz => Err(Error::UnsupportedCliParameter {
+                kind: "compression format for Parquet output",
+                value: "xz".to_owned(),
+            }
+            .no_span()),
+            Self::Bzip2 => Err(Error::UnsupportedCliParameter {
+                kind: "compression format for Parquet output",
+                value: "bzip2".to_owned(),
+            }
+            .no_span()),
+        }
+    }
+}
+
+/// An LZ4 frame encoder which writes the end of the frame when dropped, like the other encoders.
+struct Lz4Writer<W: Write>(Option<lz4::Encoder<W>>);
+
+impl<W: Write> Write for Lz4Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.as_mut().expect("encoder should not be finished").write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.as_mut().expect("encoder should not be finished").flush()
+    }
+}
+
+impl<W: Write> Drop for Lz4Writer<W> {
+    fn drop(&mut self) {
+        if let Some(encoder) = self.0.take() {
+            // Errors cannot be reported from `drop()`, same as `GzEncoder` and `XzEncoder`.
+            encoder.finish().1.ok();
         }
     }
 }
 
+/// A Brotli compressor which can be finished through a mutable reference.
+struct BrotliWriter<W: Write>(Option<brotli::CompressorWriter<W>>);
+
+impl<W: Write> Write for BrotliWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.as_mut().expect("encoder should not be finished").write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.as_mut().expect("encoder should not be finished").flush()
+    }
+}
+
+/// An output which is only complete after it is explicitly finished, e.g. a compressor which still
+/// has to write the end of its stream, or an object being uploaded into S3. An output dropped
+/// without being finished is abandoned.
+pub(crate) trait Output: Write + Send {
+    /// Finishes the output after everything is written into it.
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+impl<T: Output + ?Sized> Output for Box<T> {
+    fn finish(&mut self) -> io::Result<()> {
+        (**self).finish()
+    }
+}
+
+/// Writes out the buffer and finishes the inner output.
+///
+/// The inner output is not flushed first, since flushing a compressor ends the current block and
+/// changes the compressed content.
+fn finish_buffered<W: Output>(writer: BufWriter<W>) -> io::Result<()> {
+    writer.into_inner().map_err(io::IntoInnerError::into_error)?.finish()
+}
+
+impl Output for File {
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Output for io::Sink {
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Output for io::Stdout {
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+impl Output for TcpStream {
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+impl Output for &mut Vec<u8> {
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Output for StdoutChunkWriter {
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl Output for PostgresCopyWriter {
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl Output for MySqlInsertWriter {
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+#[cfg(feature = "s3")]
+impl Output for S3Writer {
+    fn finish(&mut self) -> io::Result<()> {
+        self.complete()
+    }
+}
+
+impl<W: Output> Output for GzEncoder<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        self.try_finish()?;
+        self.get_mut().finish()
+    }
+}
+
+impl<W: Output> Output for XzEncoder<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        self.try_finish()?;
+        self.get_mut().finish()
+    }
+}
+
+impl<W: Output> Output for zstd::Encoder<'_, W> {
+    fn finish(&mut self) -> io::Result<()> {
+        self.do_finish()?;
+        self.get_mut().finish()
+    }
+}
+
+impl<W: Output> Output for Lz4Writer<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        let encoder = self.0.take().expect("encoder should not be finished");
+        let (mut inner, res) = encoder.finish();
+        res?;
+        inner.finish()
+    }
+}
+
+impl<W: Output> Output for BzEncoder<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        self.try_finish()?;
+        self.get_mut().finish()
+    }
+}
+
+impl<W: Output> Output for BrotliWriter<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        let encoder = self.0.take().expect("encoder should not be finished");
+        // Flushing would end the current meta-block and change the compressed content, so the
+        // end of the stream is written by `into_inner()`.
+        encoder.into_inner().finish()
+    }
+}
+
+impl<W: Output> Output for snap::write::FrameEncoder<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        // A Snappy frame stream has no end marker, so it only needs to be flushed.
+        self.flush()?;
+        self.get_mut().finish()
+    }
+}
+
+impl<W: Output> Output for ParallelCompressor<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        self.try_finish()?;
+        self.get_mut().finish()
+    }
+}
+
 /// Names of the components to be produced `dbgen`.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, ValueEnum)]
 #[repr(u8)]
@@ -860,12 +2329,176 @@ impl ComponentName {
     }
 }
 
+/// A buffered writer to a (possibly compressed) data file.
+type DataFile = BufWriter<Box<dyn Output>>;
+
+/// The random number generator, the file generator thread and the `rownum` of the first row of a
+/// data file to be written.
+type FileTask = (Box<dyn RngCore + Send>, FileInfo, u64);
+
+/// A [`writer::Writer`] which encodes the rows by itself into an output file it owns, or into a
+/// database.
+trait FileWriter: writer::Writer + Send {
+    /// Returns the number of bytes written to the output so far.
+    fn output_size(&self) -> u64;
+
+    /// Concludes the file and returns the output.
+    fn into_output(self: Box<Self>) -> Result<DataFile, S<Error>>;
+}
+
+#[cfg(feature = "parquet")]
+impl FileWriter for ParquetWriter<DataFile> {
+    fn output_size(&self) -> u64 {
+        self.bytes_written()
+    }
+
+    fn into_output(mut self: Box<Self>) -> Result<DataFile, S<Error>> {
+        self.finish()
+    }
+}
+
+impl FileWriter for PgCopyBinaryWriter<DataFile> {
+    fn output_size(&self) -> u64 {
+        self.bytes_written()
+    }
+
+    fn into_output(self: Box<Self>) -> Result<DataFile, S<Error>> {
+        let mut writer = *self;
+        writer.finish()?;
+        Ok(writer.into_inner())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl FileWriter for SqliteWriter<'_> {
+    fn output_size(&self) -> u64 {
+        0
+    }
+
+    fn into_output(self: Box<Self>) -> Result<DataFile, S<Error>> {
+        Ok(BufWriter::with_capacity(0, Box::new(sink())))
+    }
+}
+
+/// How a [`FormatWriter`] encodes the rows.
+#[derive(Copy, Clone)]
+pub(crate) enum Encoder<'a> {
+    /// Values are written one by one into [`FormatWriter::writer`] using a textual format.
+    Text(&'a dyn Format),
+    /// Rows are collected into row groups of a Parquet file.
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::basic::Compression),
+    /// Rows are encoded in the binary format of PostgreSQL `COPY`.
+    PgCopyBinary,
+    /// Rows are inserted into a SQLite database.
+    #[cfg(feature = "sqlite")]
+    Sqlite(&'a SqliteDatabase, &'a Options),
+}
+
+/// The size limit of every data file.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum TargetSize {
+    /// Limits the number of bytes before compression.
+    Uncompressed(u64),
+    /// Limits the number of bytes written to the disk, i.e. after compression.
+    Compressed(u64),
+}
+
+/// Statistics of a data file, collected while writing it.
+#[derive(Default)]
+struct FileStats {
+    /// Number of bytes written before compression.
+    size: AtomicU64,
+    /// Number of bytes written to the disk, i.e. after compression.
+    disk_size: AtomicU64,
+    /// The SHA-256 digest of the bytes written to the disk, if requested.
+    sha256: Option<Mutex<Sha256>>,
+}
+
+/// A data file opened by a [`FormatWriter`].
+pub(crate) struct OpenedFile {
+    /// Path of the file, without the compression extension.
+    path: PathBuf,
+    /// Number of rows written into the file.
+    pub(crate) rows_count: u64,
+    /// The `rownum` of the first and last rows written into the file.
+    row_nums: Option<(u64, u64)>,
+    /// Statistics of the file.
+    stats: Arc<FileStats>,
+}
+
+impl OpenedFile {
+    /// Converts into a [`WrittenFile`] with the final statistics, after the file is closed.
+    fn into_written_file(self, table_index: usize, file_index: u32, path: PathBuf) -> WrittenFile {
+        let sha256 = self.stats.sha256.as_ref().map(|sha256| {
+            let sha256 = sha256.lock().unwrap_or_else(PoisonError::into_inner).clone();
+            HEXLOWER.encode(&sha256.finalize())
+        });
+        WrittenFile {
+            table_index,
+            file_index,
+            path,
+            rows_count: self.rows_count,
+            row_nums: self.row_nums,
+            size: self.stats.size.load(Ordering::Relaxed),
+            disk_size: self.stats.disk_size.load(Ordering::Relaxed),
+            sha256,
+        }
+    }
+}
+
+/// A writer collecting the statistics of the output file.
+struct FileStatsWriter<W: Write> {
+    /// The output.
+    inner: W,
+    /// The statistics of the file.
+    stats: Arc<FileStats>,
+    /// Whether `inner` is the file on disk, or the compressor in front of it.
+    is_disk: bool,
+}
+
+impl<W: Write> Write for FileStatsWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let bytes_written = self.inner.write(buf)?;
+        if self.is_disk {
+            self.stats.disk_size.fetch_add(bytes_written as u64, Ordering::Relaxed);
+            if let Some(sha256) = &self.stats.sha256 {
+                sha256
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .update(&buf[..bytes_written]);
+            }
+        } else {
+            self.stats.size.fetch_add(bytes_written as u64, Ordering::Relaxed);
+        }
+        Ok(bytes_written)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Output> Output for FileStatsWriter<W> {
+    fn finish(&mut self) -> io::Result<()> {
+        self.inner.finish()
+    }
+}
+
 /// A [`Writer`] which counts how many bytes are written.
-struct FormatWriter<'a> {
+pub(crate) struct FormatWriter<'a> {
     /// The target writer.
-    writer: BufWriter<Box<dyn Write>>,
+    writer: DataFile,
+    /// The writer owning the output file, if the rows are not encoded by a textual format.
+    file_writer: Option<Box<dyn FileWriter + 'a>>,
     /// Total number of bytes currently written into `writer`.
     written_size: u64,
+    /// Statistics of the current file. The sizes lag behind `written_size` by the data buffered
+    /// in `writer` and the compressor.
+    stats: Arc<FileStats>,
+    /// The `rownum` of the row being written.
+    row_num: u64,
+    /// The value of `written_size` when `writer` was last flushed.
+    flushed_written_size: u64,
     /// Total number of bytes written which is not yet committed into
     /// the `WRITTEN_SIZE` global variable.
     uncommitted_size: u64,
@@ -873,35 +2506,116 @@ struct FormatWriter<'a> {
     path_prefix: PathBuf,
     /// The extension of the path.
     path_extension: &'static str,
-    /// The file size limit and the associated lexicographical counter for when
-    /// size-splitting is needed.
-    target_size_and_counter: Option<(u64, LexCtr)>,
-    /// The output file format.
-    format: &'a dyn Format,
+    /// The file size limit, when size-splitting is needed.
+    target_size: Option<TargetSize>,
+    /// The lexicographical counter appended to the path, when the writer may write multiple files.
+    counter: Option<LexCtr>,
+    /// The output file encoder.
+    encoder: Encoder<'a>,
+    /// All files opened so far.
+    pub(crate) opened_files: Vec<OpenedFile>,
+    /// Number of INSERT statements in every transaction, if transactions are written.
+    transaction_size: Option<NonZeroU64>,
+    /// Number of INSERT statements written in the current transaction.
+    transaction_statements_count: u64,
 }
 impl<'a> FormatWriter<'a> {
-    /// Creates a new [`WriteWrapper`].
-    fn new(
+    /// Creates a new [`FormatWriter`].
+    pub(crate) fn new(
         path_prefix: PathBuf,
         path_extension: &'static str,
-        target_size: Option<u64>,
-        format: &'a dyn Format,
+        target_size: Option<TargetSize>,
+        encoder: Encoder<'a>,
+        transaction_size: Option<NonZeroU64>,
     ) -> Self {
         Self {
             writer: BufWriter::with_capacity(0, Box::new(sink())),
+            file_writer: None,
             written_size: 0,
+            stats: Arc::default(),
+            row_num: 0,
+            flushed_written_size: 0,
             uncommitted_size: 0,
             path_prefix,
             path_extension,
-            target_size_and_counter: target_size.map(|s| (s, LexCtr::default())),
-            format,
+            target_size,
+            counter: target_size.map(|_| LexCtr::default()),
+            encoder,
+            opened_files: Vec::new(),
+            transaction_size,
+            transaction_statements_count: 0,
+        }
+    }
+
+    /// Returns the textual format, or `None` if rows are written by a [`FileWriter`].
+    fn text_format(&self) -> Option<&'a dyn Format> {
+        match self.encoder {
+            Encoder::Text(format) => Some(format),
+            Encoder::PgCopyBinary => None,
+            #[cfg(feature = "parquet")]
+            Encoder::Parquet(_) => None,
+            #[cfg(feature = "sqlite")]
+            Encoder::Sqlite(..) => None,
+        }
+    }
+
+    /// Starts writing into a new output file.
+    pub(crate) fn open(&mut self, output: Box<dyn Output>, schema: &Schema<'_>) {
+        self.opened_files.push(OpenedFile {
+            path: self.path(),
+            rows_count: 0,
+            row_nums: None,
+            stats: Arc::clone(&self.stats),
+        });
+        self.transaction_statements_count = 0;
+        let output = BufWriter::new(output);
+        self.file_writer = match self.encoder {
+            Encoder::Text(_) => {
+                self.writer = output;
+                None
+            }
+            #[cfg(feature = "parquet")]
+            Encoder::Parquet(compression) => Some(Box::new(ParquetWriter::new(output, schema, compression))),
+            Encoder::PgCopyBinary => Some(Box::new(PgCopyBinaryWriter::new(output, self.path(), schema))),
+            #[cfg(feature = "sqlite")]
+            Encoder::Sqlite(database, options) => Some(Box::new(SqliteWriter::new(database, schema, options))),
+        };
+    }
+
+    /// Concludes the current file and flushes everything into the output.
+    fn finish(&mut self) -> Result<(), S<Error>> {
+        if let Some(writer) = self.file_writer.take() {
+            self.writer = writer.into_output()?;
         }
+        self.writer.flush().with_path_fn("flush data file", || self.path())
+    }
+
+    /// Writes the file trailer and closes the current file. The next file opened is given the next
+    /// path.
+    pub(crate) fn close(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        self.write_file_trailer(schema)?;
+        self.finish()?;
+        self.release()?;
+        if let Some(counter) = &mut self.counter {
+            counter.inc();
+        }
+        self.written_size = 0;
+        self.flushed_written_size = 0;
+        Ok(())
+    }
+
+    /// Finishes and drops the output after the current file is completely written.
+    fn release(&mut self) -> Result<(), S<Error>> {
+        let writer = mem::replace(&mut self.writer, BufWriter::with_capacity(0, Box::new(sink())));
+        // Finishing the output publishes the file under its final path, after which the
+        // statistics are complete.
+        finish_buffered(writer).with_path_fn("finish data file", || self.path())
     }
 
     /// Returns the current file path.
     fn path(&self) -> PathBuf {
         let mut path_prefix = self.path_prefix.as_os_str().to_owned();
-        if let Some((_, counter)) = &self.target_size_and_counter {
+        if let Some(counter) = &self.counter {
             path_prefix.push(counter.to_string());
         }
         path_prefix.push(".");
@@ -909,16 +2623,48 @@ impl<'a> FormatWriter<'a> {
         path_prefix.into()
     }
 
+    /// Returns new statistics for the next file to be opened.
+    fn new_file_stats(&mut self, with_sha256: bool) -> Arc<FileStats> {
+        self.stats = Arc::new(FileStats {
+            sha256: with_sha256.then(Mutex::default),
+            ..FileStats::default()
+        });
+        Arc::clone(&self.stats)
+    }
+
+    /// Records that a row is written into the current file.
+    fn count_row(&mut self) {
+        let file = self.opened_files.last_mut().expect("a file should be opened");
+        file.rows_count += 1;
+        let first_row_num = file.row_nums.map_or(self.row_num, |(first, _)| first);
+        file.row_nums = Some((first_row_num, self.row_num));
+    }
+
     /// Checks if the current written size exceeds the size limit.
-    fn try_rotate(&mut self) -> bool {
-        if let Some((size, counter)) = &mut self.target_size_and_counter {
-            if self.written_size >= *size {
+    fn try_rotate(&mut self) -> Result<bool, S<Error>> {
+        let exceeded = match self.target_size {
+            None => return Ok(false),
+            Some(TargetSize::Uncompressed(size)) => self.written_size >= size,
+            Some(TargetSize::Compressed(size)) => {
+                // Assume the data not yet flushed to the disk is incompressible, and only flush to
+                // measure the exact compressed size when this estimation reaches the limit. This
+                // keeps the number of flushes (which reduce the compression ratio) small.
+                let unflushed_size = self.written_size - self.flushed_written_size;
+                if self.stats.disk_size.load(Ordering::Relaxed) + unflushed_size >= size {
+                    self.flush().with_path_fn("flush data file", || self.path())?;
+                    self.flushed_written_size = self.written_size;
+                }
+                self.stats.disk_size.load(Ordering::Relaxed) >= size
+            }
+        };
+        if exceeded {
+            if let Some(counter) = &mut self.counter {
                 counter.inc();
-                self.written_size = 0;
-                return true;
             }
+            self.written_size = 0;
+            self.flushed_written_size = 0;
         }
-        false
+        Ok(exceeded)
     }
 }
 
@@ -936,54 +2682,505 @@ impl Write for FormatWriter<'_> {
 
 impl writer::Writer for FormatWriter<'_> {
     fn write_value(&mut self, value: &Value) -> Result<(), S<Error>> {
-        self.format
-            .write_value(self, value)
-            .with_path_fn("write value", || self.path())
+        match self.text_format() {
+            Some(format) => format.write_value(self, value),
+            None => return self.file_writer().write_value(value),
+        }
+        .with_path_fn("write value", || self.path())
     }
     fn write_file_header(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
-        self.format
-            .write_file_header(self, schema)
-            .with_path_fn("write file header", || self.path())
+        match self.text_format() {
+            Some(format) => format.write_file_header(self, schema),
+            None => return self.file_writer().write_file_header(schema),
+        }
+        .with_path_fn("write file header", || self.path())
     }
     fn write_header(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
-        self.format
-            .write_header(self, schema)
-            .with_path_fn("write header", || self.path())
+        self.count_row();
+        match self.text_format() {
+            Some(format) => {
+                if self.transaction_size.map(NonZeroU64::get) == Some(self.transaction_statements_count) {
+                    self.transaction_statements_count = 0;
+                    format
+                        .write_transaction_separator(self)
+                        .with_path_fn("write transaction separator", || self.path())?;
+                }
+                format.write_header(self, schema)
+            }
+            None => return self.file_writer().write_header(schema),
+        }
+        .with_path_fn("write header", || self.path())
     }
     fn write_value_header(&mut self, column: &str) -> Result<(), S<Error>> {
-        self.format
-            .write_value_header(self, column)
-            .with_path_fn("write value header", || self.path())
+        match self.text_format() {
+            Some(format) => format.write_value_header(self, column),
+            None => return self.file_writer().write_value_header(column),
+        }
+        .with_path_fn("write value header", || self.path())
     }
     fn write_value_separator(&mut self) -> Result<(), S<Error>> {
-        self.format
-            .write_value_separator(self)
-            .with_path_fn("write value separator", || self.path())
+        match self.text_format() {
+            Some(format) => format.write_value_separator(self),
+            None => return self.file_writer().write_value_separator(),
+        }
+        .with_path_fn("write value separator", || self.path())
     }
-    fn write_row_separator(&mut self) -> Result<(), S<Error>> {
-        self.format
-            .write_row_separator(self)
-            .with_path_fn("write row separator", || self.path())
+    fn write_row_separator(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        self.count_row();
+        match self.text_format() {
+            Some(format) => format.write_row_separator(self, schema),
+            None => return self.file_writer().write_row_separator(schema),
+        }
+        .with_path_fn("write row separator", || self.path())
     }
-    fn write_trailer(&mut self) -> Result<(), S<Error>> {
-        self.format
-            .write_trailer(self)
-            .with_path_fn("write trailer", || self.path())
+    fn write_trailer(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        if let Some(format) = self.text_format() {
+            self.transaction_statements_count += 1;
+            return format
+                .write_trailer(self, schema)
+                .with_path_fn("write trailer", || self.path());
+        }
+        let writer = self.file_writer();
+        let old_size = writer.output_size();
+        writer.write_trailer(schema)?;
+        let bytes_written = writer.output_size() - old_size;
+        self.written_size += bytes_written;
+        self.uncommitted_size += bytes_written;
+        Ok(())
     }
-}
+    fn write_file_trailer(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        match self.text_format() {
+            Some(format) => format.write_file_trailer(self, schema),
+            None => return self.file_writer().write_file_trailer(schema),
+        }
+        .with_path_fn("write file trailer", || self.path())
+    }
+}
+
+impl<'a> FormatWriter<'a> {
+    /// Returns the writer owning the output file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this writer is using a textual format.
+    fn file_writer(&mut self) -> &mut (dyn FileWriter + 'a) {
+        self.file_writer.as_deref_mut().expect("not writing with a file writer")
+    }
+}
+
+/// A partition directory written by a [`PartitionedWriter`].
+struct Partition<'a> {
+    /// The writer of the data files in the directory.
+    writer: FormatWriter<'a>,
+    /// When the partition was last written into, if its file is open.
+    last_used: Option<u64>,
+    /// Whether an INSERT statement of the partition is not yet concluded.
+    in_statement: bool,
+}
+
+/// A [`Writer`] routing every row of a table into the data files of its partition directory,
+/// i.e. `TABLE/COLUMN=VALUE/part-NNNN.EXT`, by the values of the partition columns. The partition
+/// columns themselves are not written into the data files.
+///
+/// Only a limited number of files are kept open. Writing into another partition closes the file
+/// of the least recently used partition, and its next row starts a new file.
+struct PartitionedWriter<'a> {
+    /// The environmental data.
+    env: &'a Env,
+    /// The file generator thread.
+    info: &'a FileInfo,
+    /// Index of the table in [`Env::tables`].
+    table_index: usize,
+    /// The partition columns.
+    partitioning: &'a Partitioning,
+    /// The schema of the data files, excluding the partition columns.
+    schema: Schema<'a>,
+    /// The directory of the table.
+    table_dir: PathBuf,
+    /// The output file encoder.
+    encoder: Encoder<'a>,
+    /// All partitions written so far, in the order they are first written.
+    partitions: Vec<Partition<'a>>,
+    /// The index in `partitions` of every partition directory.
+    partition_indices: HashMap<PathBuf, usize>,
+    /// The partitions with an open file, keyed by when they were last written into.
+    open_partitions: BTreeMap<u64, usize>,
+    /// The partitions written into by the current INSERT statement.
+    active_partitions: Vec<usize>,
+    /// Number of rows routed so far, which orders the `last_used` times.
+    clock: u64,
+    /// The values of the row being written, if any.
+    pending_row: Option<Vec<Value>>,
+    /// The `rownum` of the row being written.
+    pending_row_num: u64,
+    /// The `rownum` of the rows to be written.
+    row_num: u64,
+    /// Total number of bytes written which is not yet committed into
+    /// the `WRITTEN_SIZE` global variable.
+    uncommitted_size: u64,
+}
+
+impl<'a> PartitionedWriter<'a> {
+    /// Creates a new [`PartitionedWriter`] of the table at the given index.
+    fn new(env: &'a Env, info: &'a FileInfo, table_index: usize, encoder: Encoder<'a>) -> Option<Self> {
+        let table = &env.tables[table_index];
+        let partitioning = env.partitionings[table_index].as_ref()?;
+        Some(Self {
+            env,
+            info,
+            table_index,
+            partitioning,
+            schema: table
+                .schema(env.qualified)
+                .with_column_name_ranges(&partitioning.data_column_name_ranges),
+            table_dir: env.out_dir.join(table.name.unique_name()),
+            encoder,
+            partitions: Vec::new(),
+            partition_indices: HashMap::new(),
+            open_partitions: BTreeMap::new(),
+            active_partitions: Vec::new(),
+            clock: 0,
+            pending_row: None,
+            pending_row_num: 0,
+            row_num: 0,
+            uncommitted_size: 0,
+        })
+    }
+
+    /// Returns the index of the partition of a row, creating the partition if it is new.
+    fn find_partition(&mut self, row: &[Value]) -> Result<usize, S<Error>> {
+        let mut dir = self.table_dir.clone();
+        for (index, name) in &self.partitioning.columns {
+            dir.push(partition_dir_name(name, row.get(*index).unwrap_or(&Value::Null))?);
+        }
+        if let Some(index) = self.partition_indices.get(&dir) {
+            return Ok(*index);
+        }
+        self.env.create_dir_all(&dir, "create partition directory")?;
+        let path_prefix = dir.join(format!("part-{0:01$}", self.info.file_index, self.env.file_num_digits));
+        let mut writer = FormatWriter::new(
+            path_prefix,
+            self.env.format.extension(),
+            self.env.file_size,
+            self.encoder,
+            self.env.format_options.transaction_size,
+        );
+        // The file is reopened after being closed, which always needs a new path.
+        writer.counter.get_or_insert_with(LexCtr::default);
+        let index = self.partitions.len();
+        self.partitions.push(Partition {
+            writer,
+            last_used: None,
+            in_statement: false,
+        });
+        self.partition_indices.insert(dir, index);
+        Ok(index)
+    }
+
+    /// Marks the partition as the most recently used, opening its next file if it is closed. The
+    /// file of the least recently used partition is closed if too many files are open.
+    fn use_partition(&mut self, index: usize) -> Result<(), S<Error>> {
+        self.clock += 1;
+        if let Some(last_used) = self.partitions[index].last_used.replace(self.clock) {
+            self.open_partitions.remove(&last_used);
+        } else {
+            if self.open_partitions.len() >= self.env.max_open_partitions.get() {
+                if let Some((_, evicted)) = self.open_partitions.pop_first() {
+                    self.close_partition(evicted)?;
+                }
+            }
+            let writer = &mut self.partitions[index].writer;
+            self.env
+                .open_next_file(writer, self.table_index, self.info, &self.schema)?;
+        }
+        self.open_partitions.insert(self.clock, index);
+        Ok(())
+    }
+
+    /// Concludes the INSERT statement of the partition if any, and closes its file.
+    fn close_partition(&mut self, index: usize) -> Result<(), S<Error>> {
+        let partition = &mut self.partitions[index];
+        partition.last_used = None;
+        if mem::take(&mut partition.in_statement) {
+            partition.writer.write_trailer(&self.schema)?;
+            self.active_partitions.retain(|i| *i != index);
+        }
+        partition.writer.close(&self.schema)?;
+        self.uncommitted_size += mem::take(&mut partition.writer.uncommitted_size);
+        Ok(())
+    }
+
+    /// Writes the row being written, if any, into the file of its partition.
+    fn route_row(&mut self) -> Result<(), S<Error>> {
+        let Some(row) = self.pending_row.take() else {
+            return Ok(());
+        };
+        let index = self.find_partition(&row)?;
+        self.use_partition(index)?;
+
+        let partition = &mut self.partitions[index];
+        let writer = &mut partition.writer;
+        writer.row_num = self.pending_row_num;
+        if mem::replace(&mut partition.in_statement, true) {
+            writer.write_row_separator(&self.schema)?;
+        } else {
+            writer.write_header(&self.schema)?;
+            self.active_partitions.push(index);
+        }
+        let partition_columns = &self.partitioning.columns;
+        let values = row
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| partition_columns.iter().all(|(index, _)| index != i))
+            .map(|(_, value)| value);
+        for (col_index, (column, value)) in self.schema.column_names().zip(values).enumerate() {
+            if col_index != 0 {
+                writer.write_value_separator()?;
+            }
+            writer.write_value_header(column)?;
+            writer.write_value(value)?;
+        }
+        Ok(())
+    }
+
+    /// Starts writing a new row, after routing the previous one.
+    fn start_row(&mut self) -> Result<(), S<Error>> {
+        self.route_row()?;
+        let columns_count = self.partitioning.columns.len() + self.partitioning.data_column_name_ranges.len();
+        self.pending_row = Some(Vec::with_capacity(columns_count));
+        self.pending_row_num = self.row_num;
+        Ok(())
+    }
+}
+
+impl writer::Writer for PartitionedWriter<'_> {
+    fn write_value(&mut self, value: &Value) -> Result<(), S<Error>> {
+        self.pending_row
+            .as_mut()
+            .expect("a row should be started")
+            .push(value.clone());
+        Ok(())
+    }
+    fn write_file_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        // Every file of the partitions gets its header when opened.
+        Ok(())
+    }
+    fn write_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.start_row()
+    }
+    fn write_value_header(&mut self, _: &str) -> Result<(), S<Error>> {
+        Ok(())
+    }
+    fn write_value_separator(&mut self) -> Result<(), S<Error>> {
+        Ok(())
+    }
+    fn write_row_separator(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.start_row()
+    }
+    fn write_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.route_row()?;
+        for index in self.active_partitions.drain(..) {
+            let partition = &mut self.partitions[index];
+            partition.in_statement = false;
+            partition.writer.write_trailer(&self.schema)?;
+            self.env
+                .rotate_file(&mut partition.writer, self.table_index, self.info, &self.schema)?;
+            self.uncommitted_size += mem::take(&mut partition.writer.uncommitted_size);
+        }
+        Ok(())
+    }
+    fn write_file_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.route_row()?;
+        while let Some((_, index)) = self.open_partitions.pop_first() {
+            self.close_partition(index)?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`Writer`] of a table without any rows in the data file, which writes nothing.
+struct EmptyWriter;
+
+impl writer::Writer for EmptyWriter {
+    fn write_value(&mut self, _: &Value) -> Result<(), S<Error>> {
+        Ok(())
+    }
+    fn write_file_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+    fn write_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+    fn write_value_header(&mut self, _: &str) -> Result<(), S<Error>> {
+        Ok(())
+    }
+    fn write_value_separator(&mut self) -> Result<(), S<Error>> {
+        Ok(())
+    }
+    fn write_row_separator(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+    fn write_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+    fn write_file_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+}
+
+/// The writer of a table used by [`Env::write_data_file()`].
+enum TableWriter<'a> {
+    /// Writes all rows into a single sequence of data files.
+    File(FormatWriter<'a>),
+    /// Routes the rows into partition directories.
+    Partitioned(PartitionedWriter<'a>),
+    /// Opens no files, because the table has no rows in the data file.
+    Empty(EmptyWriter),
+}
+
+impl TableWriter<'_> {
+    /// Returns the underlying writer.
+    fn as_writer(&mut self) -> &mut dyn Writer {
+        match self {
+            Self::File(w) => w,
+            Self::Partitioned(w) => w,
+            Self::Empty(w) => w,
+        }
+    }
+
+    /// Sets the `rownum` of the rows to be written.
+    fn set_row_num(&mut self, row_num: u64) {
+        match self {
+            Self::File(w) => w.row_num = row_num,
+            Self::Partitioned(w) => w.row_num = row_num,
+            Self::Empty(_) => {}
+        }
+    }
+
+    /// Returns the number of bytes written which is not yet committed into the `WRITTEN_SIZE`
+    /// global variable, and resets it.
+    fn take_uncommitted_size(&mut self) -> u64 {
+        match self {
+            Self::File(w) => mem::take(&mut w.uncommitted_size),
+            Self::Partitioned(w) => mem::take(&mut w.uncommitted_size),
+            Self::Empty(_) => 0,
+        }
+    }
+
+    /// Writes the file trailers and closes all files. Returns every file opened.
+    fn close(&mut self, schema: &Schema<'_>) -> Result<Vec<OpenedFile>, S<Error>> {
+        match self {
+            Self::File(w) => {
+                w.close(schema)?;
+                Ok(mem::take(&mut w.opened_files))
+            }
+            Self::Partitioned(w) => {
+                w.write_file_trailer(schema)?;
+                Ok(w.partitions
+                    .iter_mut()
+                    .flat_map(|partition| partition.writer.opened_files.drain(..))
+                    .collect())
+            }
+            Self::Empty(_) => Ok(Vec::new()),
+        }
+    }
+}
+
+impl writer::Writer for TableWriter<'_> {
+    fn write_value(&mut self, value: &Value) -> Result<(), S<Error>> {
+        self.as_writer().write_value(value)
+    }
+    fn write_file_header(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        self.as_writer().write_file_header(schema)
+    }
+    fn write_header(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        self.as_writer().write_header(schema)
+    }
+    fn write_value_header(&mut self, column: &str) -> Result<(), S<Error>> {
+        self.as_writer().write_value_header(column)
+    }
+    fn write_value_separator(&mut self) -> Result<(), S<Error>> {
+        self.as_writer().write_value_separator()
+    }
+    fn write_row_separator(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        self.as_writer().write_row_separator(schema)
+    }
+    fn write_trailer(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        self.as_writer().write_trailer(schema)
+    }
+    fn write_file_trailer(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>> {
+        self.as_writer().write_file_trailer(schema)
+    }
+}
 
 /// The environmental data shared by all data writers.
 struct Env {
     out_dir: PathBuf,
     file_num_digits: usize,
     tables: Vec<Table>,
+    /// The last `rownum` having rows of every table, if limited by the row count of its root table.
+    max_row_nums: Vec<Option<u64>>,
     qualified: bool,
     rows_count: u32,
     format: FormatName,
     format_options: Options,
     compression: Option<(CompressionName, u8)>,
+    compress_threads: u32,
+    #[cfg(feature = "parquet")]
+    parquet_compression: parquet::basic::Compression,
     components_mask: u8,
-    file_size: Option<u64>,
+    file_size: Option<TargetSize>,
+    /// The SQLite database receiving all rows, when using the sqlite format.
+    #[cfg(feature = "sqlite")]
+    database: Option<SqliteDatabase>,
+    /// The PostgreSQL database receiving all rows, when the output is a `postgres://` URL.
+    #[cfg(feature = "postgres")]
+    postgres: Option<Arc<PostgresDatabase>>,
+    /// The MySQL database receiving all rows, when the output is a `mysql://` URL.
+    #[cfg(feature = "mysql")]
+    mysql: Option<Arc<MySqlDatabase>>,
+    /// The S3 bucket receiving all files, when the output is an `s3://` URL.
+    #[cfg(feature = "s3")]
+    s3: Option<Arc<S3Bucket>>,
+    /// Whether everything is written to the standard output (or the TCP connection) instead of
+    /// files.
+    stdout: bool,
+    /// The TCP connection receiving everything, when the output is a `tcp://` address.
+    tcp: Option<TcpStream>,
+    /// Number of INSERT statements after which the data files are flushed, when streaming the data
+    /// to the standard output or a database.
+    flush_interval: Option<NonZeroU64>,
+    /// Whether `manifest.json` is written, which requires computing the checksum of every file.
+    manifest: bool,
+    /// The checkpoint of a resumable run.
+    checkpoint: Option<Checkpoint>,
+    /// How the rows of every table are routed into partition directories, if at all.
+    partitionings: Vec<Option<Partitioning>>,
+    /// Maximum number of partition files kept open by every file generator thread.
+    max_open_partitions: NonZeroUsize,
+    /// How fast the rows are generated, if limited.
+    rate_limit: Option<RateLimit>,
+}
+
+/// A data file produced by [`Env::write_data_file()`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WrittenFile {
+    /// Index of the table in [`Env::tables`].
+    table_index: usize,
+    /// Index of the file generator thread which produced the file.
+    file_index: u32,
+    /// Path of the file.
+    path: PathBuf,
+    /// Number of rows written into the file.
+    rows_count: u64,
+    /// The `rownum` of the first and last rows written into the file.
+    row_nums: Option<(u64, u64)>,
+    /// Size of the file before compression.
+    size: u64,
+    /// Size of the file on disk.
+    disk_size: u64,
+    /// The SHA-256 checksum of the file on disk in hex, if computed.
+    sha256: Option<String>,
 }
 
 /// Information specific to a file and its derived tables.
@@ -991,11 +3188,109 @@ struct FileInfo {
     file_index: u32,
     inserts_count: u32,
     last_insert_rows_count: u32,
+    /// The sender to the thread writing to the standard output or the TCP connection, if streaming.
+    stdout_sender: Option<SyncSender<Vec<u8>>>,
 }
 
 impl Env {
+    /// Creates a file in the output directory, or an object in the S3 bucket.
+    // ALLOW_REASON: the files are always on the disk without the s3 feature.
+    #[cfg_attr(not(feature = "s3"), allow(clippy::unused_self))]
+    fn create_file(&self, path: &Path, action: &'static str) -> Result<Box<dyn Output>, S<Error>> {
+        #[cfg(feature = "s3")]
+        if let Some(s3) = &self.s3 {
+            let relative_path = path.strip_prefix(&self.out_dir).unwrap_or(path);
+            return Ok(Box::new(S3Writer::new(Arc::clone(s3), relative_path)));
+        }
+        Ok(Box::new(File::create(path).with_path(action, path)?))
+    }
+
+    /// Creates a data file in the output directory, which is published under its final path only
+    /// after it is completely written, or an object in the S3 bucket.
+    // ALLOW_REASON: the files are always on the disk without the s3 feature.
+    #[cfg_attr(not(feature = "s3"), allow(clippy::unused_self))]
+    fn create_data_file(&self, path: PathBuf) -> Result<Box<dyn Output>, S<Error>> {
+        #[cfg(feature = "s3")]
+        if self.s3.is_some() {
+            return self.create_file(&path, "create data file");
+        }
+        Ok(Box::new(PendingFile::create(path)?))
+    }
+
+    /// Creates a directory in the output directory. Nothing is done for an S3 bucket, where the
+    /// directories are implied by the object keys.
+    // ALLOW_REASON: the files are always on the disk without the s3 feature.
+    #[cfg_attr(not(feature = "s3"), allow(clippy::unused_self))]
+    fn create_dir_all(&self, path: &Path, action: &'static str) -> Result<(), S<Error>> {
+        #[cfg(feature = "s3")]
+        if self.s3.is_some() {
+            return Ok(());
+        }
+        create_dir_all(path).with_path(action, path)
+    }
+
+    /// Creates a schema file in the output directory, or returns the standard output when
+    /// streaming.
+    fn create_schema_file(&self, file_name: &str, action: &'static str) -> Result<(PathBuf, DataFile), S<Error>> {
+        if self.stdout {
+            let output: Box<dyn Output> = match &self.tcp {
+                Some(tcp) => Box::new(tcp.try_clone().with_path(action, &self.out_dir)?),
+                None => Box::new(io::stdout()),
+            };
+            return Ok((self.out_dir.clone(), BufWriter::new(output)));
+        }
+        let path = self.out_dir.join(file_name);
+        let file = self.create_file(&path, action)?;
+        Ok((path, BufWriter::new(file)))
+    }
+
+    /// Executes SQL statements in the database receiving the rows, if any. Returns whether the
+    /// statements are executed.
+    // ALLOW_REASON: nothing is executed when all database features are disabled.
+    #[cfg_attr(
+        not(any(feature = "sqlite", feature = "postgres", feature = "mysql")),
+        allow(clippy::unnecessary_wraps, clippy::unused_self, unused_variables)
+    )]
+    fn execute_in_database(&self, sql: &str) -> Result<bool, S<Error>> {
+        #[cfg(feature = "sqlite")]
+        if let Some(database) = &self.database {
+            database.execute_batch(sql).no_span_err()?;
+            return Ok(true);
+        }
+        #[cfg(feature = "postgres")]
+        if let Some(postgres) = &self.postgres {
+            postgres.execute_batch(sql).no_span_err()?;
+            return Ok(true);
+        }
+        #[cfg(feature = "mysql")]
+        if let Some(mysql) = &self.mysql {
+            mysql.execute_batch(sql).no_span_err()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Prints the number of rows loaded into every table, when loading into a database.
+    // ALLOW_REASON: nothing is printed when all database features are disabled.
+    #[cfg_attr(not(any(feature = "postgres", feature = "mysql")), allow(clippy::unused_self))]
+    fn print_rows_counts(&self) {
+        #[cfg(feature = "postgres")]
+        for (table_name, rows_count) in self.postgres.iter().flat_map(|postgres| postgres.rows_counts()) {
+            println!("Loaded {rows_count} rows into {table_name}");
+        }
+        #[cfg(feature = "mysql")]
+        for (table_name, rows_count) in self.mysql.iter().flat_map(|mysql| mysql.rows_counts()) {
+            println!("Loaded {rows_count} rows into {table_name}");
+        }
+    }
+
     /// Writes the `CREATE SCHEMA` schema files.
     fn write_schema_schema(&self) -> Result<(), S<Error>> {
+        // SQLite does not support `CREATE SCHEMA`.
+        #[cfg(feature = "sqlite")]
+        if self.database.is_some() {
+            return Ok(());
+        }
         let mut schema_names = HashMap::with_capacity(1);
         for table in &self.tables {
             if let (Some(unique_name), Some(name)) = (table.name.unique_schema_name(), table.name.schema_name()) {
@@ -1003,52 +3298,309 @@ impl Env {
             }
         }
         for (unique_name, name) in schema_names {
-            let path = self.out_dir.join(format!("{unique_name}-schema-create.sql"));
-            let mut file = BufWriter::new(File::create(&path).with_path("create schema schema file", &path)?);
+            if self.execute_in_database(&format!("CREATE SCHEMA {name};"))? {
+                continue;
+            }
+            let (path, mut file) =
+                self.create_schema_file(&format!("{unique_name}-schema-create.sql"), "create schema schema file")?;
             writeln!(file, "CREATE SCHEMA {name};").with_path("write schema schema file", &path)?;
+            finish_buffered(file).with_path("flush schema schema file", &path)?;
         }
         Ok(())
     }
 
-    /// Writes the `CREATE TABLE` schema files.
+    /// Writes the `CREATE TABLE` schema files, or executes them when writing into a database.
     fn write_table_schema(&self) -> Result<(), S<Error>> {
         for table in &self.tables {
-            let path = self.out_dir.join(format!("{}-schema.sql", table.name.unique_name()));
-            let mut file = BufWriter::new(File::create(&path).with_path("create table schema file", &path)?);
-            write!(
-                file,
+            let sql = format!(
                 "CREATE TABLE {} {}",
                 table.name.table_name(self.qualified),
                 table.content
-            )
-            .with_path("write table schema file", &path)?;
+            );
+            if self.execute_in_database(&sql)? {
+                continue;
+            }
+            let (path, mut file) = self.create_schema_file(
+                &format!("{}-schema.sql", table.name.unique_name()),
+                "create table schema file",
+            )?;
+            file.write_all(sql.as_bytes())
+                .with_path("write table schema file", &path)?;
+            // The schema files have no trailing line break, but the next statement in the stream
+            // should still start on its own line.
+            if self.stdout {
+                file.write_all(b"\n").with_path("write table schema file", &path)?;
+            }
+            finish_buffered(file).with_path("flush table schema file", &path)?;
         }
         Ok(())
     }
 
-    fn open_data_file(&self, path: PathBuf) -> Result<Box<dyn Write>, S<Error>> {
-        Ok(if !ComponentName::Data.is_in(self.components_mask) {
-            Box::new(sink())
-        } else if let Some((compression, level)) = self.compression {
+    /// Writes the `LOAD DATA` statements of the data files into a file for each table.
+    fn write_load_data_statements(&self, written_files: &[&WrittenFile]) -> Result<(), S<Error>> {
+        let format = MySqlTsvFormat(&self.format_options);
+        for (table_index, table) in self.tables.iter().enumerate() {
+            let path = self.out_dir.join(format!("{}-load.sql", table.name.unique_name()));
+            let mut file = BufWriter::new(self.create_file(&path, "create LOAD DATA file")?);
+            let schema = table.schema(self.qualified);
+            for written_file in written_files.iter().filter(|f| f.table_index == table_index) {
+                let file_name = written_file.path.file_name().unwrap_or_default().to_string_lossy();
+                format
+                    .write_load_data_statement(&mut file, &schema, &file_name)
+                    .with_path("write LOAD DATA file", &path)?;
+            }
+            finish_buffered(file).with_path("flush LOAD DATA file", &path)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the files listing the data files written, i.e. the `LOAD DATA` statements of the
+    /// mysql-tsv format and `manifest.json` (given the arguments, seed and RNG engine of the run).
+    ///
+    /// The files written by previous runs recorded in the checkpoint are also listed.
+    fn write_file_listings(
+        &self,
+        written_files: &[Vec<WrittenFile>],
+        manifest: Option<(serde_json::Value, Seed, RngName)>,
+    ) -> Result<(), S<Error>> {
+        let resumed_files = self
+            .checkpoint
+            .iter()
+            .flat_map(|checkpoint| checkpoint.completed.values());
+        let mut written_files = written_files.iter().chain(resumed_files).flatten().collect::<Vec<_>>();
+        // The sort is stable, so the files of the same thread remain in the order written.
+        written_files.sort_by_key(|written_file| written_file.file_index);
+        if self.format == FormatName::MySqlTsv && ComponentName::Data.is_in(self.components_mask) && !self.stdout {
+            self.write_load_data_statements(&written_files)?;
+        }
+        if let Some((args, seed, rng)) = manifest {
+            self.write_manifest(args, seed, rng, &written_files)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `manifest.json` describing the run and every data file.
+    fn write_manifest(
+        &self,
+        args: serde_json::Value,
+        seed: Seed,
+        rng: RngName,
+        written_files: &[&WrittenFile],
+    ) -> Result<(), S<Error>> {
+        let files = written_files
+            .iter()
+            .map(|written_file| ManifestFile {
+                table: self.tables[written_file.table_index].name.table_name(true),
+                file_index: written_file.file_index,
+                path: written_file
+                    .path
+                    .strip_prefix(&self.out_dir)
+                    .unwrap_or(&written_file.path)
+                    .to_string_lossy(),
+                first_rownum: written_file.row_nums.map(|(first, _)| first),
+                last_rownum: written_file.row_nums.map(|(_, last)| last),
+                rows_count: written_file.rows_count,
+                uncompressed_size: written_file.size,
+                compressed_size: written_file.disk_size,
+                sha256: written_file.sha256.as_deref().unwrap_or_default(),
+            })
+            .collect();
+        let manifest = Manifest {
+            version: crate::FULL_VERSION.trim_start(),
+            seed,
+            rng,
+            args,
+            files,
+        };
+        let path = self.out_dir.join("manifest.json");
+        let file = BufWriter::new(self.create_file(&path, "create manifest file")?);
+        manifest.write_to(file).with_path("write manifest file", &path)
+    }
+
+    /// Returns the path of a data file after appending the compression extension.
+    fn data_file_path(&self, path: PathBuf) -> PathBuf {
+        if let Some((compression, _)) = self.compression {
             let mut path = path.into_os_string();
             path.push(".");
             path.push(compression.extension());
-            let path = PathBuf::from(path);
-            compression.wrap(File::create(&path).with_path("create data file", &path)?, level)
+            PathBuf::from(path)
         } else {
-            Box::new(File::create(&path).with_path("create data file", &path)?)
-        })
+            path
+        }
     }
 
-    /// Writes the data file.
-    fn write_data_file(&self, info: &FileInfo, state: &mut State) -> Result<(), S<Error>> {
+    // ALLOW_REASON: the table index is only used when loading into PostgreSQL.
+    #[cfg_attr(not(any(feature = "postgres", feature = "mysql")), allow(unused_variables))]
+    fn open_data_file(
+        &self,
+        path: PathBuf,
+        table_index: usize,
+        info: &FileInfo,
+        stats: Arc<FileStats>,
+    ) -> Result<Box<dyn Output>, S<Error>> {
+        if !ComponentName::Data.is_in(self.components_mask) {
+            return Ok(Box::new(sink()));
+        }
+        if let Some(sender) = &info.stdout_sender {
+            return Ok(Box::new(StdoutChunkWriter::new(sender.clone())));
+        }
+        #[cfg(feature = "sqlite")]
+        if self.database.is_some() {
+            return Ok(Box::new(sink()));
+        }
+        #[cfg(feature = "postgres")]
+        if let Some(postgres) = &self.postgres {
+            return Ok(Box::new(PostgresCopyWriter::new(Arc::clone(postgres), table_index)));
+        }
+        #[cfg(feature = "mysql")]
+        if let Some(mysql) = &self.mysql {
+            return Ok(Box::new(MySqlInsertWriter::new(Arc::clone(mysql), table_index)));
+        }
+        let path = self.data_file_path(path);
+        let file = self.create_data_file(path.clone())?;
+        let file = FileStatsWriter {
+            inner: file,
+            stats: Arc::clone(&stats),
+            is_disk: true,
+        };
+        let output = if let Some((compression, level)) = self.compression {
+            compression
+                .wrap(file, level, self.compress_threads)
+                .with_path("initialize compression of", &path)?
+        } else {
+            Box::new(file)
+        };
+        Ok(Box::new(FileStatsWriter {
+            inner: output,
+            stats,
+            is_disk: false,
+        }))
+    }
+
+    /// Opens the next file of the writer and writes its file header.
+    fn open_next_file(
+        &self,
+        w: &mut FormatWriter<'_>,
+        table_index: usize,
+        info: &FileInfo,
+        schema: &Schema<'_>,
+    ) -> Result<(), S<Error>> {
+        let file_stats = w.new_file_stats(self.manifest);
+        w.open(self.open_data_file(w.path(), table_index, info, file_stats)?, schema);
+        w.write_file_header(schema)
+    }
+
+    /// Closes the current file of the writer and opens the next one, if the size limit is reached.
+    fn rotate_file(
+        &self,
+        w: &mut FormatWriter<'_>,
+        table_index: usize,
+        info: &FileInfo,
+        schema: &Schema<'_>,
+    ) -> Result<(), S<Error>> {
+        if w.try_rotate()? {
+            w.write_file_trailer(schema)?;
+            w.finish()?;
+            w.release()?;
+            self.open_next_file(w, table_index, info, schema)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the data files in order into the standard output or the TCP connection, on every
+    /// thread of the pool.
+    fn write_data_files_to_output(
+        &self,
+        pool: &ThreadPool,
+        files: Box<dyn Iterator<Item = FileTask> + Send>,
+        new_state: &(dyn Fn(u64, Box<dyn RngCore + Send>) -> State + Sync),
+    ) -> Result<(), S<Error>> {
+        let tcp = self
+            .tcp
+            .as_ref()
+            .map(TcpStream::try_clone)
+            .transpose()
+            .with_path("clone TCP connection", &self.out_dir)?;
+        let (receivers_sender, receivers) = channel();
+        let output_thread = spawn(move || write_chunks_to_output(tcp, receivers));
+        // Files are claimed in order, so the file being written to the output is always in progress
+        // and the workers blocked on later files will eventually be unblocked.
+        let queue = Mutex::new((files, receivers_sender));
+        let res = pool
+            .broadcast(|_| {
+                loop {
+                    let (seed, file_info, row_num) = {
+                        let mut queue = queue.lock().unwrap();
+                        let Some((seed, mut file_info, row_num)) = queue.0.next() else {
+                            return Ok(());
+                        };
+                        let (sender, receiver) = sync_channel(STDOUT_CHUNKS_PER_FILE);
+                        file_info.stdout_sender = Some(sender);
+                        // The output thread stops receiving only after failing to write, which
+                        // is reported below.
+                        queue.1.send(receiver).ok();
+                        (seed, file_info, row_num)
+                    };
+                    let mut state = new_state(row_num, seed);
+                    if let Err(e) = self.write_data_file(&file_info, &mut state) {
+                        // Stop the other workers, which never run out of files when streaming.
+                        queue.lock().unwrap().0 = Box::new(iter::empty());
+                        return Err(e);
+                    }
+                }
+            })
+            .into_iter()
+            .collect::<Result<(), _>>();
+        // Closing the channel lets the output thread finish after writing the last file.
+        drop(queue);
+        let output_res = output_thread.join().unwrap().with_path("write output", &self.out_dir);
+        // A failure writing the output also causes the workers to fail, so it is reported first.
+        output_res.and(res)
+    }
+
+    /// Writes the data file, and returns the list of files written.
+    fn write_data_file(&self, info: &FileInfo, state: &mut State) -> Result<Vec<WrittenFile>, S<Error>> {
+        let mut row_num = state.row_num;
         let path_suffix = format!(".{0:01$}", info.file_index, self.file_num_digits);
         let format = self.format.create(&self.format_options);
 
+        let mut table_index = 0;
         let mut fwe = writer::Env::new(&self.tables, state, self.qualified, |table| {
-            let path = self.out_dir.join([table.name.unique_name(), &path_suffix].concat());
-            let mut w = FormatWriter::new(path, self.format.extension(), self.file_size, &*format);
-            w.writer = BufWriter::new(self.open_data_file(w.path())?);
+            let encoder = match self.format {
+                #[cfg(feature = "parquet")]
+                FormatName::Parquet => Encoder::Parquet(self.parquet_compression),
+                FormatName::PgCopyBinary => Encoder::PgCopyBinary,
+                #[cfg(feature = "sqlite")]
+                FormatName::Sqlite => Encoder::Sqlite(
+                    self.database.as_ref().expect("database should be opened"),
+                    &self.format_options,
+                ),
+                _ => Encoder::Text(format.as_deref().expect("format should be textual")),
+            };
+            let partitioned_writer = PartitionedWriter::new(self, info, table_index, encoder)
+                .filter(|_| ComponentName::Data.is_in(self.components_mask));
+            let w = if self.max_row_nums[table_index].is_some_and(|max_row_num| max_row_num < row_num) {
+                // All rows of the table are written by the previous files.
+                TableWriter::Empty(EmptyWriter)
+            } else if let Some(w) = partitioned_writer {
+                TableWriter::Partitioned(w)
+            } else {
+                let path = self.out_dir.join([table.name.unique_name(), &path_suffix].concat());
+                let mut w = FormatWriter::new(
+                    path,
+                    self.format.extension(),
+                    self.file_size,
+                    encoder,
+                    self.format_options.transaction_size,
+                );
+                let file_stats = w.new_file_stats(self.manifest);
+                w.open(
+                    self.open_data_file(w.path(), table_index, info, file_stats)?,
+                    &table.schema(self.qualified),
+                );
+                TableWriter::File(w)
+            };
+            table_index += 1;
             Ok(w)
         })?;
 
@@ -1059,24 +3611,60 @@ impl Env {
                 self.rows_count
             };
             for _ in 0..rows_count {
+                for (_, w) in fwe.tables() {
+                    w.set_row_num(row_num);
+                }
                 fwe.write_row()?;
+                row_num += 1;
             }
             fwe.write_trailer()?;
 
             let mut total_uncommitted_size = 0;
-            for (table, w) in fwe.tables() {
-                total_uncommitted_size += mem::take(&mut w.uncommitted_size);
-                if w.try_rotate() {
-                    let new_path = w.path();
-                    w.writer.flush().with_path("flush old file for rotation", &new_path)?;
-                    w.writer = BufWriter::new(self.open_data_file(new_path)?);
-                    w.write_file_header(&table.schema(self.qualified))?;
+            for (table_index, (table, w)) in fwe.tables().enumerate() {
+                total_uncommitted_size += w.take_uncommitted_size();
+                // The partitioned writers rotate the files of every partition by themselves.
+                let TableWriter::File(w) = w else { continue };
+                if self
+                    .flush_interval
+                    .is_some_and(|interval| u64::from(i + 1) % interval.get() == 0)
+                {
+                    if self.stdout {
+                        w.flush().with_path("write output", &self.out_dir)?;
+                    } else {
+                        w.flush().with_path_fn("load into database", || w.path())?;
+                    }
                 }
+                self.rotate_file(w, table_index, info, &table.schema(self.qualified))?;
             }
             WRITTEN_SIZE.fetch_add(total_uncommitted_size, Ordering::Relaxed);
             WRITE_PROGRESS.fetch_add(rows_count.into(), Ordering::Relaxed);
+            if let Some(rate_limit) = &self.rate_limit {
+                rate_limit.wait(
+                    WRITE_PROGRESS.load(Ordering::Relaxed),
+                    WRITTEN_SIZE.load(Ordering::Relaxed),
+                );
+            }
         }
-        Ok(())
+
+        let mut opened_files = Vec::new();
+        for (table_index, (table, w)) in fwe.tables().enumerate() {
+            let files = w.close(&table.schema(self.qualified))?;
+            opened_files.extend(files.into_iter().map(|file| (table_index, file)));
+        }
+        let entry = CheckpointEntry {
+            file_index: info.file_index,
+            files: opened_files
+                .into_iter()
+                .map(|(table_index, file)| {
+                    let path = self.data_file_path(file.path.clone());
+                    file.into_written_file(table_index, info.file_index, path)
+                })
+                .collect(),
+        };
+        if let Some(checkpoint) = &self.checkpoint {
+            checkpoint.record(&entry)?;
+        }
+        Ok(entry.files)
     }
 }
 
@@ -1084,11 +3672,9 @@ impl Env {
 ///
 /// This function will loop and update the progress bar every 0.5 seconds, until [`WRITE_FINISHED`]
 /// becomes `true`.
-fn run_progress_thread(total_rows: u64) {
+fn run_progress_thread<T: Write + Send + 'static>(mb: MultiBar<T>, total_rows: u64) {
     const TICK_FORMAT: &str = "🕐🕑🕒🕓🕔🕕🕖🕗🕘🕙🕚🕛";
 
-    let mb = MultiBar::new();
-
     let mut pb = mb.create_bar(total_rows);
 
     let mut speed_bar = mb.create_bar(0);
@@ -1125,8 +3711,12 @@ fn run_progress_thread(total_rows: u64) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pending_file::temp_file_path;
+    use std::fs::read;
 
     #[test]
+    // ALLOW_REASON: the test is just a long list of test cases.
+    #[allow(clippy::too_many_lines)]
     fn test_row_args() {
         let test_cases = vec![
             (
@@ -1265,4 +3855,467 @@ mod tests {
             assert_eq!(args.row_args(), row_args);
         }
     }
+    #[test]
+    fn test_upsert_requires_dialect() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let make_args = |dialect| Args {
+            template_string: Some("CREATE TABLE a (id INT {{ rownum }}, x INT {{ 1 }});".to_owned()),
+            out_dir: out_dir.path().to_owned(),
+            insert_mode: InsertMode::Upsert,
+            dialect,
+            components: vec![ComponentName::Data],
+            quiet: true,
+            ..Args::default()
+        };
+        assert!(run(make_args(None), &mut Registry::default()).is_err());
+        run(make_args(Some(Dialect::MySQL)), &mut Registry::default()).unwrap();
+        assert_eq!(
+            read_to_string(out_dir.path().join("a.1.sql")).unwrap(),
+            "INSERT INTO a VALUES\n(1, 1)\nON DUPLICATE KEY UPDATE id = VALUES(id), x = VALUES(x);\n"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_conflict_columns() {
+        let args: Args =
+            serde_json::from_str(r#"{"conflict_target": "a=id", "update_columns": ["x", "b=y"]}"#).unwrap();
+        assert_eq!(args.conflict_target, ["a=id"]);
+        assert_eq!(args.update_columns, ["x", "b=y"]);
+    }
+
+    #[test]
+    fn test_restricted_args() {
+        let args = Args {
+            manifest: true,
+            resume: true,
+            ..Args::default()
+        };
+        validate_output_args(&args, OutputKind::Directory, "when writing to an output directory").unwrap();
+        let message = |kind, context| validate_output_args(&args, kind, context).unwrap_err().to_string();
+        assert_eq!(
+            message(OutputKind::Stream, "when writing to the standard output"),
+            "--manifest cannot be used when writing to the standard output"
+        );
+        assert_eq!(
+            message(OutputKind::S3, "when writing to S3"),
+            "--resume cannot be used when writing to S3"
+        );
+
+        let args = Args {
+            stream: true,
+            ..Args::default()
+        };
+        validate_output_args(&args, OutputKind::Stream, "when writing to the standard output").unwrap();
+        assert!(validate_output_args(&args, OutputKind::Directory, "when writing to an output directory").is_err());
+    }
+
+    #[test]
+    fn test_csv_options() {
+        let message = |args: Args| csv_options(&args).unwrap_err().to_string();
+        assert_eq!(
+            message(Args {
+                csv_quote_policy: Some(CsvQuotePolicy::Never),
+                ..Args::default()
+            }),
+            "--csv-quote-policy never requires --csv-escape"
+        );
+        assert_eq!(
+            message(Args {
+                csv_delimiter: Some('"'),
+                ..Args::default()
+            }),
+            "--csv-delimiter and --csv-quote must be different"
+        );
+        assert_eq!(
+            message(Args {
+                csv_quote: Some('\n'),
+                ..Args::default()
+            }),
+            "--csv-delimiter and --csv-quote cannot be a line break"
+        );
+        assert_eq!(
+            message(Args {
+                csv_delimiter: Some('\r'),
+                ..Args::default()
+            }),
+            "--csv-delimiter and --csv-quote cannot be a line break"
+        );
+
+        let options = csv_options(&Args {
+            csv_quote_policy: Some(CsvQuotePolicy::Never),
+            csv_escape: Some('\\'),
+            ..Args::default()
+        })
+        .unwrap();
+        assert_eq!(options.escape, Some(b'\\'));
+    }
+
+    #[test]
+    fn test_mysql_tsv_rejects_compression() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let args = Args {
+            template_string: Some("CREATE TABLE a (id INT {{ rownum }});".to_owned()),
+            out_dir: out_dir.path().to_owned(),
+            format: FormatName::MySqlTsv,
+            compression: Some(CompressionName::Gzip),
+            components: vec![ComponentName::Data],
+            quiet: true,
+            ..Args::default()
+        };
+        let error = run(args, &mut Registry::default()).unwrap_err().to_string();
+        assert!(
+            error.contains("--compression cannot be used with the mysql-tsv format"),
+            "{error}"
+        );
+        assert!(!out_dir.path().join("a-load.sql").exists());
+    }
+
+    #[test]
+    fn test_brotli_rejects_compress_threads() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let args = Args {
+            template_string: Some("CREATE TABLE a (id INT {{ rownum }});".to_owned()),
+            out_dir: out_dir.path().to_owned(),
+            compression: Some(CompressionName::Brotli),
+            compress_threads: 2,
+            quiet: true,
+            ..Args::default()
+        };
+        let error = run(args, &mut Registry::default()).unwrap_err().to_string();
+        assert!(
+            error.contains("--compress-threads cannot be used with brotli"),
+            "{error}"
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_file() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let make_args = |sqlite_overwrite| Args {
+            template_string: Some("CREATE TABLE a (id INT {{ rownum }});".to_owned()),
+            out_dir: out_dir.path().to_owned(),
+            format: FormatName::Sqlite,
+            sqlite_file: Some(PathBuf::from("gen.sqlite3")),
+            sqlite_overwrite,
+            quiet: true,
+            ..Args::default()
+        };
+        run(make_args(false), &mut Registry::default()).unwrap();
+        let path = out_dir.path().join("gen.sqlite3");
+        assert!(path.exists());
+        assert!(!out_dir.path().join("data.db").exists());
+
+        let error = run(make_args(false), &mut Registry::default()).unwrap_err().to_string();
+        assert!(error.contains("already exists, use --sqlite-overwrite"), "{error}");
+        run(make_args(true), &mut Registry::default()).unwrap();
+        let connection = rusqlite::Connection::open(path).unwrap();
+        let count: i64 = connection
+            .query_row("SELECT count(*) FROM a", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_manifest() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let args = Args {
+            template_string: Some(
+                "CREATE TABLE a ({{ rownum }});
+                /*{{ for each row of a generate 2 row of b }}*/
+                CREATE TABLE b ({{ subrownum }});"
+                    .to_owned(),
+            ),
+            out_dir: out_dir.path().to_owned(),
+            files_count: 2,
+            inserts_count: 3,
+            rows_count: 2,
+            size: Some(10),
+            compression: Some(CompressionName::Gzip),
+            manifest: true,
+            quiet: true,
+            ..Args::default()
+        };
+        run(args, &mut Registry::default()).unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_reader(File::open(out_dir.path().join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest["args"]["manifest"], true);
+        let files = manifest["files"].as_array().unwrap();
+        assert_eq!(files.len(), 16);
+        for (table, rows_count) in [("a", 12), ("b", 24)] {
+            let table_files = files.iter().filter(|f| f["table"] == table);
+            assert_eq!(
+                table_files.map(|f| f["rows_count"].as_u64().unwrap()).sum::<u64>(),
+                rows_count
+            );
+        }
+        let file = &files[1];
+        assert_eq!(file["path"], "a.1001.sql.gz");
+        assert_eq!(file["file_index"], 1);
+        assert_eq!(file["first_rownum"], 3);
+        assert_eq!(file["last_rownum"], 4);
+        let content = read(out_dir.path().join("a.1001.sql.gz")).unwrap();
+        assert_eq!(file["compressed_size"], content.len());
+        assert_eq!(file["sha256"], HEXLOWER.encode(&Sha256::digest(&content)));
+    }
+
+    #[test]
+    fn test_resume() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let make_args = |rows_count| Args {
+            template_string: Some("CREATE TABLE a ({{ rownum }}, {{ rand.range(0, 1000) }});".to_owned()),
+            out_dir: out_dir.path().to_owned(),
+            files_count: 4,
+            inserts_count: 3,
+            rows_count,
+            compression: Some(CompressionName::Gzip),
+            manifest: true,
+            resume: true,
+            quiet: true,
+            ..Args::default()
+        };
+        let data_path = |file_index| out_dir.path().join(format!("a.{file_index}.sql.gz"));
+        let read_outputs = || {
+            let mut outputs = (1..=4).map(|i| read(data_path(i)).unwrap()).collect::<Vec<_>>();
+            outputs.push(read(out_dir.path().join("manifest.json")).unwrap());
+            outputs
+        };
+        run(make_args(2), &mut Registry::default()).unwrap();
+        let expected = read_outputs();
+
+        // Simulate a run killed after completing one file, while writing the checkpoint of another.
+        let checkpoint_path = out_dir.path().join("checkpoint.jsonl");
+        let checkpoint = read_to_string(&checkpoint_path).unwrap();
+        let lines = checkpoint.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5);
+        let entry: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        let completed_index = entry["file_index"].as_u64().unwrap();
+        let partial_index = completed_index % 4 + 1;
+        std::fs::write(
+            &checkpoint_path,
+            format!("{}\n{}\n{{\"file_index\"", lines[0], lines[1]),
+        )
+        .unwrap();
+        std::fs::write(data_path(partial_index), b"partial").unwrap();
+        std::fs::remove_file(data_path(completed_index)).unwrap();
+        std::fs::remove_file(out_dir.path().join("manifest.json")).unwrap();
+
+        run(make_args(2), &mut Registry::default()).unwrap();
+        // The completed file is skipped, so it is not recreated.
+        assert!(!data_path(completed_index).exists());
+        std::fs::write(
+            data_path(completed_index),
+            &expected[usize::try_from(completed_index).unwrap() - 1],
+        )
+        .unwrap();
+        assert_eq!(read_outputs(), expected);
+        assert_eq!(read_to_string(&checkpoint_path).unwrap().lines().count(), 5);
+
+        assert!(run(make_args(3), &mut Registry::default()).is_err());
+    }
+
+    #[test]
+    fn test_per_row_rng() {
+        let generate = |files_count, inserts_count, rows_count| {
+            let out_dir = tempfile::tempdir().unwrap();
+            let args = Args {
+                template_string: Some(
+                    "CREATE TABLE a ({{ rownum }}, {{ rand.range(0, 1000000) }});
+                    /*{{ for each row of a generate 2 rows of b }}*/
+                    CREATE TABLE b ({{ rand.uuid() }});"
+                        .to_owned(),
+                ),
+                out_dir: out_dir.path().to_owned(),
+                files_count,
+                inserts_count,
+                rows_count,
+                format: FormatName::Csv,
+                seed: Some(Seed([0x55; 32])),
+                per_row_rng: true,
+                quiet: true,
+                ..Args::default()
+            };
+            run(args, &mut Registry::default()).unwrap();
+            ["a", "b"].map(|table| {
+                (1..=files_count)
+                    .map(|i| read_to_string(out_dir.path().join(format!("{table}.{i}.csv"))).unwrap())
+                    .collect::<String>()
+            })
+        };
+        let expected = generate(1, 12, 1);
+        assert_eq!(expected[0].lines().count(), 12);
+        assert_eq!(expected[1].lines().count(), 24);
+        assert_eq!(generate(3, 2, 2), expected);
+        assert_eq!(generate(4, 1, 3), expected);
+    }
+
+    #[test]
+    fn test_partition_by() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let make_args = |partition_by: &str| Args {
+            template_string: Some(
+                "CREATE TABLE a (
+                    id INT {{ rownum }},
+                    \"k\" TEXT {{ CASE mod(rownum, 3) WHEN 0 THEN NULL ELSE 'k:' || mod(rownum, 3) END }},
+                    v INT {{ rownum * 10 }}
+                );"
+                .to_owned(),
+            ),
+            out_dir: out_dir.path().to_owned(),
+            inserts_count: 2,
+            rows_count: 3,
+            format: FormatName::Csv,
+            partition_by: vec![partition_by.to_owned()],
+            max_open_partitions: NonZeroUsize::new(2),
+            quiet: true,
+            ..Args::default()
+        };
+        run(make_args("k"), &mut Registry::default()).unwrap();
+
+        // Only 2 files are kept open, so every partition is closed and reopened once.
+        let table_dir = out_dir.path().join("a");
+        for (path, content) in [
+            ("k=k%3A1/part-1000.csv", "1,10\n"),
+            ("k=k%3A1/part-1001.csv", "4,40\n"),
+            ("k=k%3A2/part-1000.csv", "2,20\n"),
+            ("k=k%3A2/part-1001.csv", "5,50\n"),
+            ("k=__HIVE_DEFAULT_PARTITION__/part-1000.csv", "3,30\n"),
+            ("k=__HIVE_DEFAULT_PARTITION__/part-1001.csv", "6,60\n"),
+        ] {
+            assert_eq!(read_to_string(table_dir.join(path)).unwrap(), content, "{path}");
+        }
+        assert_eq!(std::fs::read_dir(&table_dir).unwrap().count(), 3);
+
+        assert!(run(make_args("a=x"), &mut Registry::default()).is_err());
+        assert!(run(make_args("b=k"), &mut Registry::default()).is_err());
+        assert!(run(make_args("id,k,v"), &mut Registry::default()).is_err());
+    }
+
+    #[test]
+    fn test_stream() {
+        let make_args = |out_dir, stream| Args {
+            template_string: Some("CREATE TABLE a ({{ rownum }}, {{ rand.range(0, 1000) }});".to_owned()),
+            out_dir,
+            files_count: 5,
+            inserts_count: 2,
+            rows_count: 3,
+            stream,
+            format: FormatName::Csv,
+            components: vec![ComponentName::Data],
+            seed: Some(Seed([0x55; 32])),
+            quiet: true,
+            ..Args::default()
+        };
+        let out_dir = tempfile::tempdir().unwrap();
+        run(make_args(out_dir.path().to_owned(), false), &mut Registry::default()).unwrap();
+        let expected = (1..=5)
+            .flat_map(|i| read(out_dir.path().join(format!("a.{i}.csv"))).unwrap())
+            .collect::<Vec<_>>();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let out_dir = PathBuf::from(format!("tcp://{}", listener.local_addr().unwrap()));
+        let stream_thread = spawn(move || run(make_args(out_dir, true), &mut Registry::default()));
+        let (mut connection, _) = listener.accept().unwrap();
+        // The stream starts with the 30 rows of the fixed-size run and continues after them.
+        let mut streamed = vec![0; expected.len() * 2];
+        connection.read_exact(&mut streamed).unwrap();
+        assert_eq!(streamed[..expected.len()], expected);
+        assert!(streamed[expected.len()..].starts_with(b"31,"));
+
+        // The stream only stops when the connection is closed.
+        drop(connection);
+        assert!(stream_thread.join().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_stream_schema() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let args = Args {
+            template_string: Some("CREATE TABLE a ({{ rownum }});".to_owned()),
+            out_dir: PathBuf::from(format!("tcp://{}", listener.local_addr().unwrap())),
+            quiet: true,
+            ..Args::default()
+        };
+        let thread = spawn(move || run(args, &mut Registry::default()));
+        let (mut connection, _) = listener.accept().unwrap();
+        let mut streamed = String::new();
+        connection.read_to_string(&mut streamed).unwrap();
+        thread.join().unwrap().unwrap();
+        assert_eq!(streamed, "CREATE TABLE a ();\nINSERT INTO a VALUES\n(1);\n");
+    }
+
+    #[test]
+    fn test_clean_temp_files() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let partition_dir = out_dir.path().join("a/k=1");
+        std::fs::create_dir_all(&partition_dir).unwrap();
+        let stale_files = [
+            out_dir.path().join(".a.1.csv.tmp"),
+            out_dir.path().join(".a.7.csv.gz.tmp"),
+            partition_dir.join(".part-3.csv.tmp"),
+        ];
+        let other_files = [out_dir.path().join(".b.1.csv.tmp"), out_dir.path().join("a.9.csv")];
+        for path in stale_files.iter().chain(&other_files) {
+            std::fs::write(path, b"stale").unwrap();
+        }
+
+        let args = Args {
+            template_string: Some("CREATE TABLE a ({{ rownum }});".to_owned()),
+            out_dir: out_dir.path().to_owned(),
+            files_count: 2,
+            format: FormatName::Csv,
+            clean_temp_files: true,
+            quiet: true,
+            ..Args::default()
+        };
+        run(args, &mut Registry::default()).unwrap();
+        for path in &stale_files {
+            assert!(!path.exists(), "{}", path.display());
+        }
+        for path in &other_files {
+            assert!(path.exists(), "{}", path.display());
+        }
+        // The data files are renamed from their temporary paths after being written.
+        assert_eq!(read_to_string(out_dir.path().join("a.1.csv")).unwrap(), "1\n");
+        assert!(!temp_file_path(&out_dir.path().join("a.2.csv")).exists());
+    }
+
+    #[test]
+    fn test_table_rows() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let make_args = |table_rows: &[&str]| Args {
+            template_string: Some(
+                "CREATE TABLE a ({{ rownum }});
+                /*{{ for each row of a generate 2 rows of b }}*/
+                CREATE TABLE b ({{ subrownum }});
+                /*{{ generate 3 rows of c }}*/
+                CREATE TABLE c ({{ rownum }});"
+                    .to_owned(),
+            ),
+            out_dir: out_dir.path().to_owned(),
+            total_count: Some(4),
+            rows_per_file: Some(4),
+            format: FormatName::Csv,
+            components: vec![ComponentName::Data],
+            table_rows: table_rows.iter().map(|&arg| arg.to_owned()).collect(),
+            quiet: true,
+            ..Args::default()
+        };
+        run(make_args(&[]), &mut Registry::default()).unwrap();
+        assert_eq!(read_to_string(out_dir.path().join("a.1.csv")).unwrap(), "1\n2\n3\n4\n");
+        assert_eq!(read_to_string(out_dir.path().join("c.1.csv")).unwrap(), "1\n2\n3\n");
+
+        run(make_args(&["c=1", "a = 2"]), &mut Registry::default()).unwrap();
+        assert_eq!(read_to_string(out_dir.path().join("a.1.csv")).unwrap(), "1\n2\n");
+        assert_eq!(read_to_string(out_dir.path().join("b.1.csv")).unwrap(), "1\n2\n1\n2\n");
+        assert_eq!(read_to_string(out_dir.path().join("c.1.csv")).unwrap(), "1\n");
+
+        for invalid in [&["c"][..], &["c=x"], &["b=1"], &["d=1"], &["c=5"]] {
+            assert!(
+                run(make_args(invalid), &mut Registry::default()).is_err(),
+                "{invalid:?}"
+            );
+        }
+    }
 }
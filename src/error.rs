@@ -73,6 +73,17 @@ pub enum Error {
         create_table: String,
     },
 
+    /// Root table name does not match that of the root table directive.
+    #[error(
+        "root table name in the GENERATE ROWS and CREATE TABLE statements do not match ({generate} vs {create_table})"
+    )]
+    RootTableNameMismatch {
+        /// The table name in the GENERATE ROWS statement
+        generate: String,
+        /// The table name in the CREATE TABLE statement
+        create_table: String,
+    },
+
     /// Unexpected value type.
     #[error("cannot convert {value} into {expected}")]
     UnexpectedValueType {
@@ -98,6 +109,26 @@ pub enum Error {
     #[error("failed to configure thread pool")]
     Rayon(#[from] rayon::ThreadPoolBuildError),
 
+    /// Failed to encode a Parquet file.
+    #[cfg(feature = "parquet")]
+    #[error("failed to encode Parquet file")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    /// Failed to write into a SQLite database.
+    #[cfg(feature = "sqlite")]
+    #[error("failed to write into SQLite database")]
+    Sqlite(#[source] Box<rusqlite::Error>),
+
+    /// Failed to load into a PostgreSQL database.
+    #[cfg(feature = "postgres")]
+    #[error("failed to load into PostgreSQL database")]
+    Postgres(#[source] postgres::Error),
+
+    /// Failed to load into a MySQL database.
+    #[cfg(feature = "mysql")]
+    #[error("failed to load into MySQL database")]
+    MySql(#[source] Box<mysql::Error>),
+
     /// Cannot use `--table-name` when template contains multiple tables.
     #[error("cannot use --table-name when template contains multiple tables")]
     CannotUseTableNameForMultipleTables,
@@ -146,6 +177,27 @@ impl From<pest::error::Error<Rule>> for Error {
     }
 }
 
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Sqlite(Box::new(e))
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl From<postgres::Error> for Error {
+    fn from(e: postgres::Error) -> Self {
+        Self::Postgres(e)
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl From<mysql::Error> for Error {
+    fn from(e: mysql::Error) -> Self {
+        Self::MySql(Box::new(e))
+    }
+}
+
 impl From<regex_syntax::Error> for Error {
     fn from(e: regex_syntax::Error) -> Self {
         Self::InvalidRegex(e.into())
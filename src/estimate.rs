@@ -0,0 +1,498 @@
+//! Estimating the size of the output with `--estimate`.
+//!
+//! A sample of the rows is generated into memory, and the number of rows, size and files of every
+//! table are projected from the sample without writing anything.
+
+use crate::{
+    cli::{Args, CompressionName, Encoder, FormatName, FormatWriter, Output, eval_globals, root_table_indices},
+    error::Error,
+    eval::{CompileContext, State, Table},
+    format::Options,
+    parser::Expr,
+    span::{S, SpanExt as _},
+    writer,
+};
+use clap::ValueEnum as _;
+use rand::{Rng as _, rngs::OsRng};
+use std::{
+    fmt,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// Number of rows of the main table generated by `--estimate`.
+const ESTIMATE_SAMPLE_ROWS: u64 = 10_000;
+
+/// Number of evenly spaced ranges the rows generated by `--estimate` are taken from, so values
+/// depending on `rownum` are represented across the whole output.
+const ESTIMATE_SAMPLE_RANGES: u64 = 10;
+
+/// A writer appending into a buffer shared with its creator.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Output for SharedBuffer {
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The rows of a table generated by `--estimate`.
+#[derive(Default)]
+struct TableSample {
+    /// Number of rows generated.
+    rows_count: u64,
+    /// The encoded data files of the rows.
+    data: Vec<u8>,
+}
+
+impl TableSample {
+    /// Returns the size of the data after compression.
+    fn compressed_size(&self, compression: CompressionName, level: u8) -> u64 {
+        let mut output = Vec::new();
+        compression
+            .wrap(&mut output, level, 1)
+            .and_then(|mut writer| {
+                writer.write_all(&self.data)?;
+                writer.finish()
+            })
+            .expect("compressing into a Vec should not fail");
+        output.len() as u64
+    }
+}
+
+/// Formats a size in bytes with a binary unit, e.g. `1.50 GiB`.
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    let unit = (1..UNITS.len())
+        .rev()
+        .find(|unit| size >> (10 * unit) != 0)
+        .unwrap_or(0);
+    if unit == 0 {
+        return format!("{size} B");
+    }
+    let hundredths = (u128::from(size) * 100) >> (10 * unit);
+    format!("{}.{:02} {}", hundredths / 100, hundredths % 100, UNITS[unit])
+}
+
+/// Prints rows of cells as a table, with the first column left-aligned and the others
+/// right-aligned.
+fn print_columns(rows: &[Vec<String>]) {
+    use fmt::Write as _;
+    let mut widths = Vec::new();
+    for row in rows {
+        widths.resize(widths.len().max(row.len()), 0);
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    for row in rows {
+        let mut line = String::new();
+        for (i, (cell, width)) in row.iter().zip(&widths).enumerate() {
+            if i == 0 {
+                write!(line, "{cell:<width$}")
+            } else {
+                write!(line, "  {cell:>width$}")
+            }
+            .expect("writing into a String should not fail");
+        }
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Generates `sample_count` rows of the main table together with their derived rows, taken from
+/// evenly spaced ranges of `rownum`, and returns the encoded rows of every table.
+fn generate_estimate_samples(
+    args: &Args,
+    tables: &[Table],
+    ctx: &CompileContext,
+    seeding_rng: &mut rand_hc::Hc128Rng,
+    format_options: &Options,
+    sample_count: u64,
+) -> Result<Vec<TableSample>, S<Error>> {
+    let total_count = args.row_args().total_count;
+    #[cfg(feature = "parquet")]
+    let parquet_compression = args.parquet_compression()?;
+    let text_format = args.format.create(format_options);
+    let ranges_count = sample_count.min(ESTIMATE_SAMPLE_RANGES);
+    let mut samples = tables.iter().map(|_| TableSample::default()).collect::<Vec<_>>();
+    for range in 0..ranges_count {
+        let mut remaining = sample_count / ranges_count + u64::from(range < sample_count % ranges_count);
+        let row_num = range * (total_count / ranges_count) + 1;
+        let mut state = State::new(row_num, args.rng.create(seeding_rng), ctx.clone());
+        let mut buffers = Vec::with_capacity(tables.len());
+        let mut fwe = writer::Env::new(tables, &mut state, args.qualified, |table| {
+            let encoder = match args.format {
+                #[cfg(feature = "parquet")]
+                FormatName::Parquet => Encoder::Parquet(parquet_compression),
+                FormatName::PgCopyBinary => Encoder::PgCopyBinary,
+                _ => Encoder::Text(text_format.as_deref().expect("format should be textual")),
+            };
+            let mut w = FormatWriter::new(
+                PathBuf::new(),
+                args.format.extension(),
+                None,
+                encoder,
+                format_options.transaction_size,
+            );
+            let buffer = SharedBuffer::default();
+            w.open(Box::new(buffer.clone()), &table.schema(args.qualified));
+            buffers.push(buffer);
+            Ok(w)
+        })?;
+        while remaining > 0 {
+            let rows_count = remaining.min(args.rows_count.into());
+            for _ in 0..rows_count {
+                fwe.write_row()?;
+            }
+            fwe.write_trailer()?;
+            remaining -= rows_count;
+        }
+        for ((table, w), (sample, buffer)) in fwe.tables().zip(samples.iter_mut().zip(&buffers)) {
+            w.close(&table.schema(args.qualified))?;
+            sample.rows_count += w.opened_files.iter().map(|file| file.rows_count).sum::<u64>();
+            sample
+                .data
+                .append(&mut buffer.0.lock().unwrap_or_else(PoisonError::into_inner));
+        }
+    }
+    Ok(samples)
+}
+
+/// The projected output of a table.
+#[derive(Debug, PartialEq, Eq)]
+struct TableEstimate {
+    /// Number of rows.
+    rows_count: u64,
+    /// Size of the data files before compression.
+    size: u64,
+    /// Number of data files.
+    files_count: u64,
+}
+
+/// The projected output of a run.
+#[derive(Debug)]
+struct Estimate {
+    /// Number of rows of the main table, or `rownum`s, in the sample.
+    sample_count: u64,
+    /// The projected output of every table.
+    tables: Vec<TableEstimate>,
+    /// The projected total size of the output under every compression format, or empty if the
+    /// format already compresses the data files.
+    compressed_sizes: Vec<(CompressionName, u64)>,
+}
+
+/// Generates a sample of rows without writing anything, and projects the number of rows, size and
+/// files of every table, and the size of the output under every compression format.
+///
+/// The sample of [`ESTIMATE_SAMPLE_ROWS`] rows of the main table is scaled by the total number of
+/// rows of the main table. The tables under a root table with its own row count are scaled by that
+/// row count instead.
+fn estimate(
+    args: &Args,
+    tables: &mut [Table],
+    ctx: CompileContext,
+    global_exprs: Vec<S<Expr>>,
+    rows_counts: Vec<(usize, S<Expr>)>,
+    format_options: &Options,
+) -> Result<Estimate, S<Error>> {
+    #[cfg(feature = "sqlite")]
+    if args.format == FormatName::Sqlite {
+        return Err(Error::InvalidArguments("--estimate is not supported by the sqlite format".to_owned()).no_span());
+    }
+    if !args.partition_by.is_empty() {
+        return Err(Error::InvalidArguments("--estimate cannot be used with --partition-by".to_owned()).no_span());
+    }
+    let row_args = args.row_args();
+    let sample_count = row_args.total_count.min(ESTIMATE_SAMPLE_ROWS);
+    let mut seeding_rng = args.seed.unwrap_or_else(|| OsRng.r#gen()).make_rng();
+    let ctx = eval_globals(args, tables, ctx, global_exprs, rows_counts, &mut seeding_rng)?;
+    let tables = &*tables;
+    let samples = generate_estimate_samples(args, tables, &ctx, &mut seeding_rng, format_options, sample_count)?;
+
+    let roots = root_table_indices(tables);
+    let project = |sampled: u64, root: usize| {
+        let (total_count, sample_count) = match tables[root].rows_count {
+            Some(rows_count) => (rows_count, samples[root].rows_count),
+            None => (row_args.total_count, sample_count),
+        };
+        let projected = u128::from(sampled) * u128::from(total_count) / u128::from(sample_count.max(1));
+        u64::try_from(projected).unwrap_or(u64::MAX)
+    };
+    let target_size = args
+        .size
+        .map(|size| (size, None))
+        .or_else(|| args.compressed_size.map(|size| (size, args.compression)));
+    let rows_per_insert = u64::from(row_args.rows_count);
+
+    let table_estimates = samples
+        .iter()
+        .zip(&roots)
+        .map(|(sample, &root)| {
+            let size = project(sample.data.len() as u64, root);
+            // The rows of a root table are generated for its first `rownum`s, so only the file
+            // generator threads covering them write files: some threads writing whole files of
+            // INSERT statements, and maybe one thread writing the remaining rows.
+            let root_rows_count = tables[root].rows_count.unwrap_or(row_args.total_count);
+            let full_threads =
+                (root_rows_count / row_args.rows_per_file).min(u64::from(row_args.files_count.saturating_sub(1)));
+            let full_inserts = u64::from(row_args.inserts_count);
+            let remaining_inserts = (root_rows_count - full_threads * row_args.rows_per_file).div_ceil(rows_per_insert);
+            let files_count = match target_size {
+                None => full_threads + u64::from(remaining_inserts > 0),
+                Some((target_size, compression)) => {
+                    let size = compression.map_or(size, |compression| {
+                        project(sample.compressed_size(compression, args.compress_level), root)
+                    });
+                    // A file is rotated after the first INSERT statement reaching the target size,
+                    // and every thread opens one more file after its last rotation.
+                    let insert_size = u128::from(size) * u128::from(rows_per_insert);
+                    let inserts_per_file = if insert_size == 0 {
+                        u64::MAX
+                    } else {
+                        let inserts_per_file =
+                            (u128::from(target_size) * u128::from(root_rows_count)).div_ceil(insert_size);
+                        u64::try_from(inserts_per_file).unwrap_or(u64::MAX).max(1)
+                    };
+                    let thread_files = |inserts: u64| {
+                        if inserts == 0 {
+                            0
+                        } else {
+                            inserts / inserts_per_file + 1
+                        }
+                    };
+                    full_threads * thread_files(full_inserts) + thread_files(remaining_inserts)
+                }
+            };
+            TableEstimate {
+                rows_count: project(sample.rows_count, root),
+                size,
+                files_count,
+            }
+        })
+        .collect();
+
+    // Parquet files are compressed page by page, which is already included in the size.
+    let compressed_sizes = if args.format.compresses_pages() {
+        Vec::new()
+    } else {
+        CompressionName::value_variants()
+            .iter()
+            .map(|&compression| {
+                let size = samples
+                    .iter()
+                    .zip(&roots)
+                    .map(|(sample, &root)| project(sample.compressed_size(compression, args.compress_level), root))
+                    .sum();
+                (compression, size)
+            })
+            .collect()
+    };
+
+    Ok(Estimate {
+        sample_count,
+        tables: table_estimates,
+        compressed_sizes,
+    })
+}
+
+/// Generates a sample of rows without writing anything, and prints the projected number of rows,
+/// size and files of every table, and the size of the output under every compression format.
+pub fn print_estimate(
+    args: &Args,
+    tables: &mut [Table],
+    ctx: CompileContext,
+    global_exprs: Vec<S<Expr>>,
+    rows_counts: Vec<(usize, S<Expr>)>,
+    format_options: &Options,
+) -> Result<(), S<Error>> {
+    let estimate = estimate(args, tables, ctx, global_exprs, rows_counts, format_options)?;
+
+    let mut rows = vec![vec![
+        "table".to_owned(),
+        "rows".to_owned(),
+        "size".to_owned(),
+        "files".to_owned(),
+    ]];
+    let mut total = [0; 3];
+    for (table, table_estimate) in tables.iter().zip(&estimate.tables) {
+        let values = [
+            table_estimate.rows_count,
+            table_estimate.size,
+            table_estimate.files_count,
+        ];
+        rows.push(vec![
+            table.name.table_name(args.qualified).to_owned(),
+            values[0].to_string(),
+            format_size(values[1]),
+            values[2].to_string(),
+        ]);
+        for (total, value) in total.iter_mut().zip(values) {
+            *total += value;
+        }
+    }
+    if tables.len() > 1 {
+        rows.push(vec![
+            "total".to_owned(),
+            total[0].to_string(),
+            format_size(total[1]),
+            total[2].to_string(),
+        ]);
+    }
+    let total_count = args.row_args().total_count;
+    if tables[0].rows_count.is_some() {
+        println!(
+            "Estimated from a sample of {} of {total_count} rownums:\n",
+            estimate.sample_count
+        );
+    } else {
+        println!(
+            "Estimated from a sample of {} of {total_count} rows of {}:\n",
+            estimate.sample_count,
+            tables[0].name.table_name(args.qualified),
+        );
+    }
+    print_columns(&rows);
+
+    if !estimate.compressed_sizes.is_empty() {
+        print_compressed_sizes(args.compress_level, &estimate.compressed_sizes, total[1]);
+    }
+    Ok(())
+}
+
+/// Prints the projected size of the output under every compression format, compared with the
+/// uncompressed `total_size`.
+fn print_compressed_sizes(level: u8, compressed_sizes: &[(CompressionName, u64)], total_size: u64) {
+    let mut rows = vec![vec!["compression".to_owned(), "size".to_owned(), "ratio".to_owned()]];
+    for &(compression, size) in compressed_sizes {
+        let permille = u128::from(size) * 1000 / u128::from(total_size.max(1));
+        rows.push(vec![
+            format!("{compression:?}").to_lowercase(),
+            format_size(size),
+            format!("{}.{}%", permille / 10, permille % 10),
+        ]);
+    }
+    println!("\nCompressed with level {level}:\n");
+    print_columns(&rows);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli::run, parser::Template, span::Registry};
+
+    #[test]
+    fn test_estimate() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.50 KiB");
+        assert_eq!(format_size(69_074_610), "65.87 MiB");
+        assert_eq!(format_size(u64::MAX), "15.99 EiB");
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let out_dir = temp_dir.path().join("out");
+        let make_args = |partition_by: Vec<String>| Args {
+            template_string: Some(
+                "CREATE TABLE a (id INT {{ rownum }}, k INT {{ mod(rownum, 3) }});
+                /*{{ for each row of a generate 2 rows of b }}*/
+                CREATE TABLE b ({{ rand.uuid() }});"
+                    .to_owned(),
+            ),
+            out_dir: out_dir.clone(),
+            files_count: 4,
+            inserts_count: 100,
+            rows_count: 100,
+            size: Some(1024),
+            format: FormatName::Csv,
+            partition_by,
+            estimate: true,
+            quiet: true,
+            ..Args::default()
+        };
+        run(make_args(Vec::new()), &mut Registry::default()).unwrap();
+        assert!(!out_dir.exists());
+        assert!(run(make_args(vec!["k".to_owned()]), &mut Registry::default()).is_err());
+    }
+
+    #[test]
+    fn test_estimate_fixed_size() {
+        // Every row has the same size, so the projection from the sample is exact.
+        let template = Template::parse(
+            "CREATE TABLE a ({{ 12345678 }});
+            /*{{ for each row of a generate 2 rows of b }}*/
+            CREATE TABLE b ({{ 12 }});
+            /*{{ generate 30 rows of c }}*/
+            CREATE TABLE c ({{ 1234 }});",
+            &[],
+            None,
+            &mut Registry::default(),
+        )
+        .unwrap();
+        let ctx = CompileContext::new(template.variables_count);
+        let mut tables = template
+            .tables
+            .into_iter()
+            .map(|t| ctx.compile_table(t))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let args = Args {
+            files_count: 4,
+            inserts_count: 100,
+            rows_count: 100,
+            size: Some(1024),
+            format: FormatName::Csv,
+            estimate: true,
+            ..Args::default()
+        };
+        let estimate = estimate(
+            &args,
+            &mut tables,
+            ctx,
+            template.global_exprs,
+            template.rows_counts,
+            &Options::default(),
+        )
+        .unwrap();
+
+        assert_eq!(estimate.sample_count, 10_000);
+        assert_eq!(
+            estimate.tables,
+            [
+                // A file is rotated after 2 INSERT statements of 900 bytes of `a` or 600 bytes of `b`,
+                // so each of the 4 threads writes 50 files of its 100 statements and one more empty
+                // file, same as a real run.
+                TableEstimate {
+                    rows_count: 40_000,
+                    size: 360_000,
+                    files_count: 204,
+                },
+                TableEstimate {
+                    rows_count: 80_000,
+                    size: 240_000,
+                    files_count: 204,
+                },
+                // The 30 rows of the root table are all written by the first thread.
+                TableEstimate {
+                    rows_count: 30,
+                    size: 150,
+                    files_count: 1,
+                },
+            ]
+        );
+        assert_eq!(estimate.compressed_sizes.len(), CompressionName::value_variants().len());
+    }
+}
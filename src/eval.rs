@@ -9,7 +9,8 @@ use crate::{
     value::Value,
 };
 use chrono::{DateTime, NaiveDateTime};
-use rand::{Rng, RngCore, distributions::Bernoulli};
+use rand::{Rng, RngCore, SeedableRng, distributions::Bernoulli};
+use rand_chacha::ChaCha20Rng;
 use rand_distr::{LogNormal, Uniform, Zipf, weighted_alias::WeightedAliasIndex};
 use rand_regex::EncodedString;
 use std::{fmt, ops::Range, sync::Arc};
@@ -33,12 +34,72 @@ impl CompileContext {
     }
 }
 
+/// The random number generator of a [`State`].
+enum StateRng {
+    /// A single generator producing the values of all rows in sequence.
+    Sequential(Box<dyn RngCore>),
+    /// A counter-based generator switching to a separate stream for every row.
+    PerRow(Box<ChaCha20Rng>),
+}
+
+impl RngCore for StateRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Sequential(rng) => rng.next_u32(),
+            Self::PerRow(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Sequential(rng) => rng.next_u64(),
+            Self::PerRow(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Sequential(rng) => rng.fill_bytes(dest),
+            Self::PerRow(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            Self::Sequential(rng) => rng.try_fill_bytes(dest),
+            Self::PerRow(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+/// Identifies a row by its table and position, independent of how the rows are split into files.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct RowKey(u64, u64);
+
+/// The finalizer of `SplitMix64`, a bijection mixing all bits of the input.
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl RowKey {
+    /// Returns the key of the row of the table at `table_index`, which is the `num`-th row under
+    /// this key. Rows of root tables are under the default key, numbered by `rownum`, and rows of
+    /// derived tables are under the key of their parent row, numbered by `subrownum`.
+    pub(crate) fn child(self, table_index: usize, num: u64) -> Self {
+        let x = mix(num ^ (table_index as u64).rotate_right(16));
+        let first = mix(self.0 ^ x);
+        Self(first, mix(self.1 ^ mix(first ^ 0x9e37_79b9_7f4a_7c15)))
+    }
+}
+
 /// The external mutable state used during evaluation.
 pub struct State {
     pub(crate) row_num: u64,
     /// Defines the value of `subrownum`.
     pub sub_row_num: u64,
-    rng: Box<dyn RngCore>,
+    rng: StateRng,
     compile_context: CompileContext,
 }
 
@@ -59,17 +120,34 @@ impl State {
     /// # Parameters
     ///
     /// - `row_num`: The starting row number in this state. The first file should have this set
-    ///     to 1, and the second to `rows_count * inserts_count + 1`, etc.
+    ///   to 1, and the second to `rows_count * inserts_count + 1`, etc.
     /// - `rng`: The seeded random number generator.
     pub fn new(row_num: u64, rng: Box<dyn RngCore>, compile_context: CompileContext) -> Self {
         Self {
             row_num,
             sub_row_num: 1,
-            rng,
+            rng: StateRng::Sequential(rng),
             compile_context,
         }
     }
 
+    /// Makes every row use a separate random number generator derived from the `seed` and the
+    /// table and position of the row, instead of the generator given to [`State::new()`].
+    ///
+    /// The values of a row are then the same no matter which other rows are generated by this
+    /// state.
+    pub fn seed_per_row(&mut self, seed: [u8; 32]) {
+        self.rng = StateRng::PerRow(Box::new(ChaCha20Rng::from_seed(seed)));
+    }
+
+    /// Prepares the random number generator for evaluating the row with the given key.
+    pub(crate) fn seed_row(&mut self, row_key: RowKey) {
+        if let StateRng::PerRow(rng) = &mut self.rng {
+            rng.set_stream(row_key.0);
+            rng.set_word_pos(u128::from(row_key.1) << 4);
+        }
+    }
+
     /// Extracts the compile context from the state.
     pub fn into_compile_context(self) -> CompileContext {
         self.compile_context
@@ -94,6 +172,9 @@ pub struct Table {
     pub row: Row,
     /// Information of dervied tables (index, and number of rows to generate)
     pub derived: Vec<(usize, Compiled)>,
+    /// Number of rows of a root table with its own row count. Such a table only has rows for the
+    /// `rownum` up to this number.
+    pub rows_count: Option<u64>,
 }
 
 /// The schema information extracted from the compiled table.
@@ -107,11 +188,53 @@ pub struct Schema<'a> {
     column_name_ranges: &'a [Range<usize>],
 }
 
-impl Schema<'_> {
+impl<'a> Schema<'a> {
+    /// Returns the same schema with the columns replaced, e.g. to exclude some of them.
+    ///
+    /// The `column_name_ranges` must refer to the same `content`.
+    #[must_use]
+    pub fn with_column_name_ranges(self, column_name_ranges: &'a [Range<usize>]) -> Self {
+        Self {
+            column_name_ranges,
+            ..self
+        }
+    }
+
     /// Returns an iterator of column names associated with the table.
     pub fn column_names(&self) -> impl Iterator<Item = &str> + '_ {
         self.column_name_ranges.iter().map(move |r| &self.content[r.clone()])
     }
+
+    /// Returns an iterator of the declared column types associated with the table.
+    ///
+    /// The declared type is the text following the column name until the next top-level comma,
+    /// e.g. `decimal(10, 2) not null`. Anonymous columns have an empty declared type.
+    pub fn column_types(&self) -> impl Iterator<Item = &str> + '_ {
+        self.column_name_ranges.iter().map(move |r| {
+            if r.is_empty() {
+                return "";
+            }
+            let rest = &self.content[r.end..];
+            let mut depth = 0_usize;
+            let end = rest
+                .char_indices()
+                .find(|(_, c)| match c {
+                    '(' => {
+                        depth += 1;
+                        false
+                    }
+                    ')' if depth > 0 => {
+                        depth -= 1;
+                        false
+                    }
+                    ')' => true,
+                    ',' => depth == 0,
+                    _ => false,
+                })
+                .map_or(rest.len(), |(i, _)| i);
+            rest[..end].trim()
+        })
+    }
 }
 
 impl Table {
@@ -138,6 +261,7 @@ impl CompileContext {
                 .into_iter()
                 .map(|(i, e)| self.compile(e).map(|c| (i, c)))
                 .collect::<Result<_, _>>()?,
+            rows_count: None,
         })
     }
 }
@@ -320,6 +444,8 @@ impl Compiled {
             C::RandUniformU64(uniform) => state.rng.sample(uniform).into(),
             C::RandUniformI64(uniform) => state.rng.sample(uniform).into(),
             C::RandUniformF64(uniform) => Value::from_finite_f64(state.rng.sample(uniform)),
+            // ALLOW_REASON: the Zipf distribution only produces positive integers.
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
             C::RandZipf(zipf) => (state.rng.sample(zipf) as u64).into(),
             C::RandLogNormal(log_normal) => Value::from_finite_f64(state.rng.sample(log_normal)),
             C::RandBool(bern) => state.rng.sample(bern).into(),
@@ -3,12 +3,17 @@
 use crate::{bytes::ByteString, eval::Schema, value::Value};
 
 use chrono::{Datelike, NaiveDateTime, Timelike};
+use data_encoding::BASE64;
 use memchr::{memchr_iter, memchr2_iter, memchr3_iter};
 use rand_regex::Encoding;
+use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
+    collections::HashMap,
     io::{Error, Write},
+    num::NonZeroU64,
     slice,
+    str::FromStr,
 };
 
 /// An shared format description of how to serialize values into strings.
@@ -30,10 +35,166 @@ pub trait Format {
     fn write_value_separator(&self, writer: &mut dyn Write) -> Result<(), Error>;
 
     /// Writes the separator between the every row.
-    fn write_row_separator(&self, writer: &mut dyn Write) -> Result<(), Error>;
+    fn write_row_separator(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error>;
 
     /// Writes the content of an INSERT statement after all rows.
-    fn write_trailer(&self, writer: &mut dyn Write) -> Result<(), Error>;
+    fn write_trailer(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error>;
+
+    /// Writes the separator between every transaction.
+    fn write_transaction_separator(&self, writer: &mut dyn Write) -> Result<(), Error>;
+
+    /// Writes the content at the end of each file.
+    fn write_file_trailer(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error>;
+}
+
+/// The SQL dialect of the target database.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum), value(rename_all = "lowercase"))]
+#[serde(rename_all = "lowercase")]
+pub enum Dialect {
+    /// MySQL dialect.
+    MySQL,
+    /// PostgreSQL dialect.
+    PostgreSQL,
+    /// SQLite dialect.
+    SQLite,
+    /// Microsoft SQL Server dialect.
+    SQLServer,
+    /// Oracle dialect.
+    Oracle,
+}
+
+impl FromStr for Dialect {
+    type Err = crate::error::Error;
+    fn from_str(dialect: &str) -> Result<Self, Self::Err> {
+        Ok(match dialect {
+            "mysql" => Self::MySQL,
+            "postgresql" => Self::PostgreSQL,
+            "sqlite" => Self::SQLite,
+            "sqlserver" => Self::SQLServer,
+            "oracle" => Self::Oracle,
+            _ => {
+                return Err(crate::error::Error::UnsupportedCliParameter {
+                    kind: "SQL dialect",
+                    value: dialect.to_owned(),
+                });
+            }
+        })
+    }
+}
+
+impl Dialect {
+    /// Writes an identifier quoted in the native style of this dialect.
+    fn write_quoted_identifier(self, writer: &mut dyn Write, name: &str) -> Result<(), Error> {
+        let (open, close) = match self {
+            Self::MySQL => ("`", "`"),
+            Self::PostgreSQL | Self::SQLite | Self::Oracle => ("\"", "\""),
+            Self::SQLServer => ("[", "]"),
+        };
+        writer.write_all(open.as_bytes())?;
+        writer.write_all(name.replace(close, &close.repeat(2)).as_bytes())?;
+        writer.write_all(close.as_bytes())
+    }
+}
+
+/// The kind of statement used to insert rows in the SQL formats.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum InsertMode {
+    /// Plain `INSERT INTO` statements.
+    #[default]
+    Insert,
+    /// Skips rows conflicting with existing ones (`INSERT IGNORE` or `ON CONFLICT DO NOTHING`).
+    InsertIgnore,
+    /// Replaces existing rows conflicting with new ones (`REPLACE INTO`).
+    Replace,
+    /// Updates existing rows conflicting with new ones (`ON DUPLICATE KEY UPDATE` or
+    /// `ON CONFLICT DO UPDATE`).
+    Upsert,
+}
+
+/// How conflicting rows of a table are resolved in the [`InsertMode::InsertIgnore`] and
+/// [`InsertMode::Upsert`] modes.
+#[derive(Debug, Clone, Default)]
+pub struct ConflictClause {
+    /// Columns of the unique key which rows may conflict on. Only used by the `ON CONFLICT`
+    /// syntax of PostgreSQL and SQLite.
+    pub target: Vec<String>,
+    /// Columns to update on conflict. If absent, all columns not in the target are updated.
+    pub update_columns: Option<Vec<String>>,
+}
+
+/// When the fields of the CSV format are enclosed in quotes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum CsvQuotePolicy {
+    /// Quotes every string value.
+    #[default]
+    Strings,
+    /// Quotes every value except NULL.
+    Always,
+    /// Quotes only the values containing the delimiter, quote, escape or line break characters,
+    /// and strings which could be confused with NULL.
+    WhenNeeded,
+    /// Never quotes any values. The delimiter, quote and line break characters are escaped with
+    /// the escape character instead, which must be given.
+    Never,
+    /// Quotes every value except numbers and NULL.
+    NonNumeric,
+}
+
+/// The record terminator of the CSV format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum CsvLineTerminator {
+    /// Unix line ending (`\n`).
+    #[default]
+    Lf,
+    /// Windows line ending (`\r\n`).
+    Crlf,
+}
+
+impl CsvLineTerminator {
+    fn as_bytes(self) -> &'static [u8] {
+        match self {
+            Self::Lf => b"\n",
+            Self::Crlf => b"\r\n",
+        }
+    }
+}
+
+/// Options specific to the CSV format.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// The character separating fields.
+    pub delimiter: u8,
+    /// The character enclosing quoted fields.
+    pub quote: u8,
+    /// The character escaping special characters. If absent, quote characters inside a quoted
+    /// field are escaped by doubling them.
+    pub escape: Option<u8>,
+    /// When fields are enclosed in quotes.
+    pub quote_policy: CsvQuotePolicy,
+    /// The record terminator.
+    pub line_terminator: CsvLineTerminator,
+    /// Whether to write the UTF-8 byte order mark at the beginning of each file.
+    pub bom: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            quote_policy: CsvQuotePolicy::Strings,
+            line_terminator: CsvLineTerminator::Lf,
+            bom: false,
+        }
+    }
 }
 
 /// Common options for the formatters.
@@ -49,6 +210,23 @@ pub struct Options {
     pub false_string: Cow<'static, str>,
     /// The string to print for NULL result.
     pub null_string: Cow<'static, str>,
+    /// The SQL dialect used to render literals and quoted identifiers. If absent, values are
+    /// written in a generic form and identifiers are copied from the template verbatim.
+    pub dialect: Option<Dialect>,
+    /// The kind of statement used to insert rows in the SQL formats.
+    pub insert_mode: InsertMode,
+    /// The conflict clause of each table, keyed by the table name given in the schema. Tables
+    /// missing from this map use the default clause.
+    pub conflict_clauses: HashMap<String, ConflictClause>,
+    /// Number of INSERT statements in every transaction of the SQL formats. If absent, no
+    /// transaction statements are written.
+    pub transaction_size: Option<NonZeroU64>,
+    /// SQL statements written at the beginning of every file of the SQL formats.
+    pub preamble: Cow<'static, str>,
+    /// SQL statements written at the end of every file of the SQL formats.
+    pub postamble: Cow<'static, str>,
+    /// Options of the CSV format.
+    pub csv: CsvOptions,
 }
 
 impl Default for Options {
@@ -59,6 +237,13 @@ impl Default for Options {
             true_string: Cow::Borrowed("1"),
             false_string: Cow::Borrowed("0"),
             null_string: Cow::Borrowed("NULL"),
+            dialect: None,
+            insert_mode: InsertMode::Insert,
+            conflict_clauses: HashMap::new(),
+            transaction_size: None,
+            preamble: Cow::Borrowed(""),
+            postamble: Cow::Borrowed(""),
+            csv: CsvOptions::default(),
         }
     }
 }
@@ -75,15 +260,36 @@ pub struct CsvFormat<'a>(pub &'a Options);
 #[derive(Debug)]
 pub struct SqlInsertSetFormat<'a>(pub &'a Options);
 
+/// JSON Lines formatter, writing one JSON object per row.
+#[derive(Debug)]
+pub struct JsonLinesFormat<'a>(pub &'a Options);
+
+/// PostgreSQL `COPY` formatter, using the text format.
+#[derive(Debug)]
+pub struct PgCopyFormat<'a>(pub &'a Options);
+
+/// MySQL `LOAD DATA` formatter, using the default tab-separated format.
+#[derive(Debug)]
+pub struct MySqlTsvFormat<'a>(pub &'a Options);
+
 /// Writes a timestamp in ISO 8601 format.
-fn write_timestamp(writer: &mut dyn Write, quote: &str, timestamp: &NaiveDateTime) -> Result<(), Error> {
+///
+/// The `separator` is placed between the date and time parts. SQL accepts a space here, while
+/// strict ISO 8601 requires a `T`.
+pub(crate) fn write_timestamp(
+    writer: &mut dyn Write,
+    quote: &str,
+    separator: char,
+    timestamp: &NaiveDateTime,
+) -> Result<(), Error> {
     write!(
         writer,
-        "{}{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        "{}{:04}-{:02}-{:02}{}{:02}:{:02}:{:02}",
         quote,
         timestamp.year(),
         timestamp.month(),
         timestamp.day(),
+        separator,
         timestamp.hour(),
         timestamp.minute(),
         timestamp.second(),
@@ -96,7 +302,7 @@ fn write_timestamp(writer: &mut dyn Write, quote: &str, timestamp: &NaiveDateTim
 }
 
 /// Writes a time interval in the standard SQL format.
-fn write_interval(writer: &mut dyn Write, quote: &str, mut interval: i64) -> Result<(), Error> {
+pub(crate) fn write_interval(writer: &mut dyn Write, quote: &str, mut interval: i64) -> Result<(), Error> {
     writer.write_all(quote.as_bytes())?;
     if interval == i64::MIN {
         return write!(writer, "-106751991 04:00:54.775808{quote}");
@@ -129,18 +335,15 @@ fn write_interval(writer: &mut dyn Write, quote: &str, mut interval: i64) -> Res
 }
 
 #[derive(Debug, Copy, Clone)]
-enum EscapeRule {
-    Escape(&'static [u8]),
-    Unescape(u8),
+enum EscapeRule<'a> {
+    Escape(&'a [u8]),
 }
 
 #[derive(Debug, Default)]
 struct EscapeState {
     prev_end: usize,
-    prev_byte: u8,
     cur_start: usize,
     cur_byte: u8,
-    unescape_ready: bool,
 }
 
 impl EscapeState {
@@ -150,29 +353,14 @@ impl EscapeState {
         &bytes[self.prev_end..cur]
     }
 
-    fn apply_rule<'b>(&mut self, rule: &'b EscapeRule) -> &'b [u8] {
-        let ret = match rule {
-            EscapeRule::Escape(replacement) => {
-                self.unescape_ready = false;
-                *replacement
-            }
-            EscapeRule::Unescape(b) => {
-                if self.unescape_ready && *b == self.prev_byte && self.cur_start == self.prev_end {
-                    self.unescape_ready = false;
-                    &[]
-                } else {
-                    self.unescape_ready = true;
-                    slice::from_ref(b)
-                }
-            }
-        };
+    fn apply_rule<'b>(&mut self, rule: &'b EscapeRule<'b>) -> &'b [u8] {
+        let EscapeRule::Escape(replacement) = rule;
         self.prev_end = self.cur_start + 1;
-        self.prev_byte = self.cur_byte;
-        ret
+        replacement
     }
 }
 
-fn write_with_escape(writer: &mut dyn Write, bytes: &[u8], rules: &[(u8, EscapeRule)]) -> Result<(), Error> {
+fn write_with_escape(writer: &mut dyn Write, bytes: &[u8], rules: &[(u8, EscapeRule<'_>)]) -> Result<(), Error> {
     let mut state = EscapeState::default();
     match *rules {
         [] => {}
@@ -216,37 +404,221 @@ fn write_with_escape(writer: &mut dyn Write, bytes: &[u8], rules: &[(u8, EscapeR
 impl Options {
     fn write_sql_bytes(&self, writer: &mut dyn Write, bytes: &ByteString) -> Result<(), Error> {
         if bytes.encoding() == Encoding::Binary {
-            writer.write_all(b"X'")?;
+            let (prefix, suffix) = match self.dialect {
+                Some(Dialect::PostgreSQL) => (r"'\x", "'::bytea"),
+                Some(Dialect::SQLServer) => ("0x", ""),
+                Some(Dialect::Oracle) => ("HEXTORAW('", "')"),
+                Some(Dialect::MySQL | Dialect::SQLite) | None => ("X'", "'"),
+            };
+            writer.write_all(prefix.as_bytes())?;
             for b in bytes.as_bytes() {
                 write!(writer, "{b:02X}")?;
             }
-        } else {
-            writer.write_all(b"'")?;
-            write_with_escape(
-                writer,
-                bytes.as_bytes(),
-                if self.escape_backslash {
-                    &[
-                        (b'\'', EscapeRule::Escape(b"''")),
-                        (b'\\', EscapeRule::Escape(br"\\")),
-                        (b'\0', EscapeRule::Escape(br"\0")),
-                    ]
-                } else {
-                    &[(b'\'', EscapeRule::Escape(b"''"))]
-                },
-            )?;
+            return writer.write_all(suffix.as_bytes());
         }
+
+        writer.write_all(if self.dialect == Some(Dialect::SQLServer) {
+            b"N'"
+        } else {
+            b"'"
+        })?;
+        write_with_escape(
+            writer,
+            bytes.as_bytes(),
+            // MySQL always treats backslashes in string literals as escape characters,
+            // unless the `NO_BACKSLASH_ESCAPES` SQL mode is active.
+            if self.escape_backslash || self.dialect == Some(Dialect::MySQL) {
+                &[
+                    (b'\'', EscapeRule::Escape(b"''")),
+                    (b'\\', EscapeRule::Escape(br"\\")),
+                    (b'\0', EscapeRule::Escape(br"\0")),
+                ]
+            } else {
+                &[(b'\'', EscapeRule::Escape(b"''"))]
+            },
+        )?;
         writer.write_all(b"'")
     }
 
+    fn write_sql_timestamp(&self, writer: &mut dyn Write, timestamp: &NaiveDateTime) -> Result<(), Error> {
+        match self.dialect {
+            Some(Dialect::PostgreSQL | Dialect::Oracle) => {
+                writer.write_all(b"TIMESTAMP ")?;
+                write_timestamp(writer, "'", ' ', timestamp)
+            }
+            Some(Dialect::SQLServer) => {
+                // the ISO 8601 form is the only one not affected by the `DATEFORMAT` setting.
+                writer.write_all(b"CAST(")?;
+                write_timestamp(writer, "'", 'T', timestamp)?;
+                writer.write_all(b" AS DATETIME2)")
+            }
+            Some(Dialect::MySQL | Dialect::SQLite) | None => write_timestamp(writer, "'", ' ', timestamp),
+        }
+    }
+
+    fn write_sql_interval(&self, writer: &mut dyn Write, interval: i64) -> Result<(), Error> {
+        match self.dialect {
+            Some(Dialect::PostgreSQL) => {
+                writer.write_all(b"INTERVAL '")?;
+                write_pg_interval(writer, interval)?;
+                writer.write_all(b"'")
+            }
+            Some(Dialect::Oracle) => {
+                let sign = if interval < 0 { "-" } else { "" };
+                let interval = interval.unsigned_abs();
+                let (seconds, microseconds) = (interval / 1_000_000, interval % 1_000_000);
+                write!(
+                    writer,
+                    "INTERVAL '{sign}{} {:02}:{:02}:{:02}.{microseconds:06}' DAY(9) TO SECOND(6)",
+                    seconds / 86400,
+                    seconds / 3600 % 24,
+                    seconds / 60 % 60,
+                    seconds % 60,
+                )
+            }
+            Some(Dialect::MySQL | Dialect::SQLite | Dialect::SQLServer) | None => write_interval(writer, "'", interval),
+        }
+    }
+
+    /// Writes a possibly-qualified identifier taken from the template.
+    ///
+    /// Every quoted part is re-quoted in the native style of the dialect. Unquoted parts, and the
+    /// whole identifier when no dialect is chosen, are written unchanged.
+    fn write_sql_identifier(&self, writer: &mut dyn Write, name: &str) -> Result<(), Error> {
+        let Some(dialect) = self.dialect else {
+            return writer.write_all(name.as_bytes());
+        };
+        for (i, part) in split_qualified_name(name).enumerate() {
+            if i != 0 {
+                writer.write_all(b".")?;
+            }
+            if part.starts_with(['"', '`', '[']) {
+                dialect.write_quoted_identifier(writer, &unquote_column_name(part))?;
+            } else {
+                writer.write_all(part.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the keywords starting an SQL statement which inserts rows into a table.
+    fn write_sql_insert_into(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
+        writer.write_all(match (self.insert_mode, self.dialect) {
+            (InsertMode::InsertIgnore, Some(Dialect::MySQL) | None) => b"INSERT IGNORE INTO ",
+            (InsertMode::InsertIgnore, Some(Dialect::SQLite)) => b"INSERT OR IGNORE INTO ",
+            (InsertMode::Replace, _) => b"REPLACE INTO ",
+            _ => b"INSERT INTO ",
+        })?;
+        self.write_sql_identifier(writer, schema.name)
+    }
+
+    /// Writes the clause resolving conflicts after all rows of an SQL insert statement.
+    fn write_sql_conflict_clause(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
+        let is_mysql = matches!(self.dialect, Some(Dialect::MySQL) | None);
+        match self.insert_mode {
+            InsertMode::Insert | InsertMode::Replace => return Ok(()),
+            InsertMode::InsertIgnore if is_mysql || self.dialect == Some(Dialect::SQLite) => return Ok(()),
+            InsertMode::InsertIgnore | InsertMode::Upsert => {}
+        }
+
+        let default_clause = ConflictClause::default();
+        let clause = self.conflict_clauses.get(schema.name).unwrap_or(&default_clause);
+        let target = clause.target.iter().map(String::as_str).collect::<Vec<_>>();
+        let mut update_columns = match &clause.update_columns {
+            Some(columns) => columns.iter().map(String::as_str).collect(),
+            None => schema
+                .column_names()
+                .filter(|col| {
+                    !col.is_empty()
+                        && !target
+                            .iter()
+                            .any(|t| unquote_column_name(t) == unquote_column_name(col))
+                })
+                .collect::<Vec<_>>(),
+        };
+
+        if is_mysql {
+            // `ON DUPLICATE KEY UPDATE` requires at least one assignment.
+            if update_columns.is_empty() {
+                update_columns = schema.column_names().filter(|col| !col.is_empty()).collect();
+            }
+            // `VALUES(col)` is deprecated since MySQL 8.0.20 in favor of a row alias
+            // (`AS new ON DUPLICATE KEY UPDATE col = new.col`), but TiDB and older MySQL versions
+            // do not support the row alias.
+            writer.write_all(b"\nON DUPLICATE KEY UPDATE ")?;
+            for (i, col) in update_columns.iter().enumerate() {
+                if i != 0 {
+                    writer.write_all(b", ")?;
+                }
+                self.write_sql_identifier(writer, col)?;
+                writer.write_all(b" = VALUES(")?;
+                self.write_sql_identifier(writer, col)?;
+                writer.write_all(b")")?;
+            }
+            return Ok(());
+        }
+
+        writer.write_all(b"\nON CONFLICT ")?;
+        if !target.is_empty() {
+            writer.write_all(b"(")?;
+            for (i, col) in target.iter().enumerate() {
+                if i != 0 {
+                    writer.write_all(b", ")?;
+                }
+                self.write_sql_identifier(writer, col)?;
+            }
+            writer.write_all(b") ")?;
+        }
+        if self.insert_mode == InsertMode::InsertIgnore || update_columns.is_empty() {
+            return writer.write_all(b"DO NOTHING");
+        }
+        writer.write_all(b"DO UPDATE SET ")?;
+        for (i, col) in update_columns.iter().enumerate() {
+            if i != 0 {
+                writer.write_all(b", ")?;
+            }
+            self.write_sql_identifier(writer, col)?;
+            writer.write_all(b" = EXCLUDED.")?;
+            self.write_sql_identifier(writer, col)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the statement starting a transaction.
+    fn write_sql_begin(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        writer.write_all(match self.dialect {
+            Some(Dialect::SQLServer) => b"BEGIN TRANSACTION;\n",
+            // Oracle starts a transaction implicitly with the first statement.
+            Some(Dialect::Oracle) => b"",
+            Some(Dialect::MySQL | Dialect::PostgreSQL | Dialect::SQLite) | None => b"BEGIN;\n",
+        })
+    }
+
+    /// Writes the preamble and starts the first transaction of an SQL file.
+    fn write_sql_file_header(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        write_sql_statements(writer, &self.preamble)?;
+        if self.transaction_size.is_some() {
+            self.write_sql_begin(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Commits the last transaction and writes the postamble of an SQL file.
+    fn write_sql_file_trailer(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        if self.transaction_size.is_some() {
+            writer.write_all(b"COMMIT;\n")?;
+        }
+        write_sql_statements(writer, &self.postamble)
+    }
+
     /// Writes a value in SQL format.
     pub fn write_sql_value(&self, writer: &mut dyn Write, value: &Value) -> Result<(), Error> {
         match value {
             Value::Null => writer.write_all(self.null_string.as_bytes()),
             Value::Number(number) => number.write_io(writer, &self.true_string, &self.false_string),
             Value::Bytes(bytes) => self.write_sql_bytes(writer, bytes),
-            Value::Timestamp(timestamp) => write_timestamp(writer, "'", timestamp),
-            Value::Interval(interval) => write_interval(writer, "'", *interval),
+            Value::Timestamp(timestamp) => self.write_sql_timestamp(writer, timestamp),
+            Value::Interval(interval) => self.write_sql_interval(writer, *interval),
             Value::Array(array) => {
                 writer.write_all(b"ARRAY[")?;
                 for (i, item) in array.iter().enumerate() {
@@ -261,24 +633,71 @@ impl Options {
     }
 }
 
+/// Writes user-provided SQL statements on their own lines.
+fn write_sql_statements(writer: &mut dyn Write, statements: &str) -> Result<(), Error> {
+    if statements.is_empty() {
+        return Ok(());
+    }
+    writer.write_all(statements.as_bytes())?;
+    if !statements.ends_with('\n') {
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Splits a qualified name like `a."b.c"` into its parts, ignoring dots inside quotes.
+fn split_qualified_name(name: &str) -> impl Iterator<Item = &str> {
+    let mut rest = name;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut closing = None;
+        let mut end = rest.len();
+        let mut chars = rest.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match (closing, c) {
+                (None, '.') => {
+                    end = i;
+                    break;
+                }
+                (None, '"' | '`') => closing = Some(c),
+                (None, '[') => closing = Some(']'),
+                (Some(q), _) if c == q => {
+                    if chars.peek().is_some_and(|(_, n)| *n == q) && q != ']' {
+                        chars.next();
+                    } else {
+                        closing = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let part = &rest[..end];
+        rest = rest.get((end + 1)..).unwrap_or("");
+        Some(part)
+    })
+}
+
 impl Format for SqlFormat<'_> {
     fn write_value(&self, writer: &mut dyn Write, value: &Value) -> Result<(), Error> {
         self.0.write_sql_value(writer, value)
     }
 
-    fn write_file_header(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
-        Ok(())
+    fn write_file_header(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        self.0.write_sql_file_header(writer)
     }
 
     fn write_header(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
-        write!(writer, "INSERT INTO {} ", schema.name)?;
+        self.0.write_sql_insert_into(writer, schema)?;
+        writer.write_all(b" ")?;
         if self.0.headers {
             writer.write_all(b"(")?;
             for (i, col) in schema.column_names().enumerate() {
                 if i != 0 {
                     writer.write_all(b", ")?;
                 }
-                writer.write_all(col.as_bytes())?;
+                self.0.write_sql_identifier(writer, col)?;
             }
             writer.write_all(b") ")?;
         }
@@ -293,12 +712,29 @@ impl Format for SqlFormat<'_> {
         writer.write_all(b", ")
     }
 
-    fn write_row_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
+    fn write_row_separator(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
+        // Oracle only accepts multiple rows in one VALUES list since 23ai, so every row is written as
+        // its own statement.
+        if self.0.dialect == Some(Dialect::Oracle) {
+            self.write_trailer(writer, schema)?;
+            return self.write_header(writer, schema);
+        }
         writer.write_all(b"),\n(")
     }
 
-    fn write_trailer(&self, writer: &mut dyn Write) -> Result<(), Error> {
-        writer.write_all(b");\n")
+    fn write_trailer(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
+        writer.write_all(b")")?;
+        self.0.write_sql_conflict_clause(writer, schema)?;
+        writer.write_all(b";\n")
+    }
+
+    fn write_transaction_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        writer.write_all(b"COMMIT;\n")?;
+        self.0.write_sql_begin(writer)
+    }
+
+    fn write_file_trailer(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        self.0.write_sql_file_trailer(writer)
     }
 }
 
@@ -307,62 +743,140 @@ impl Format for SqlInsertSetFormat<'_> {
         self.0.write_sql_value(writer, value)
     }
 
-    fn write_file_header(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
-        Ok(())
+    fn write_file_header(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        self.0.write_sql_file_header(writer)
     }
 
     fn write_header(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
-        writeln!(writer, "INSERT INTO {} SET", schema.name)
+        self.0.write_sql_insert_into(writer, schema)?;
+        writer.write_all(b" SET\n")
     }
 
     fn write_value_header(&self, writer: &mut dyn Write, column: &str) -> Result<(), Error> {
-        write!(writer, "{column} = ")
+        self.0.write_sql_identifier(writer, column)?;
+        writer.write_all(b" = ")
     }
 
     fn write_value_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
         writer.write_all(b",\n")
     }
 
-    fn write_row_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
+    fn write_row_separator(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
         writer.write_all(b";\n\n")
     }
 
-    fn write_trailer(&self, writer: &mut dyn Write) -> Result<(), Error> {
+    fn write_trailer(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
+        self.0.write_sql_conflict_clause(writer, schema)?;
         writer.write_all(b";\n\n")
     }
+
+    fn write_transaction_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        writer.write_all(b"COMMIT;\n")?;
+        self.0.write_sql_begin(writer)
+    }
+
+    fn write_file_trailer(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        self.0.write_sql_file_trailer(writer)
+    }
 }
 
 impl CsvFormat<'_> {
-    fn write_bytes(&self, writer: &mut dyn Write, bytes: &ByteString) -> Result<(), Error> {
-        writer.write_all(b"\"")?;
-        write_with_escape(
-            writer,
-            bytes.as_bytes(),
-            if self.0.escape_backslash {
-                &[(b'"', EscapeRule::Escape(b"\"\"")), (b'\\', EscapeRule::Escape(br"\\"))]
-            } else {
-                &[(b'"', EscapeRule::Escape(b"\"\""))]
-            },
-        )?;
-        writer.write_all(b"\"")
+    /// Writes a field, quoting and escaping it according to the CSV options.
+    ///
+    /// Fields which are not strings are only quoted when the quote policy is not `Strings`.
+    fn write_field(&self, writer: &mut dyn Write, bytes: &[u8], is_string: bool) -> Result<(), Error> {
+        let csv = &self.0.csv;
+        let is_special = |b: &u8| [csv.delimiter, csv.quote, b'\r', b'\n'].contains(b) || Some(*b) == csv.escape;
+        let quoted = match csv.quote_policy {
+            CsvQuotePolicy::Strings => is_string,
+            CsvQuotePolicy::Always | CsvQuotePolicy::NonNumeric => true,
+            CsvQuotePolicy::WhenNeeded => {
+                (is_string && (bytes.is_empty() || bytes == self.0.null_string.as_bytes()))
+                    || bytes.iter().any(is_special)
+            }
+            CsvQuotePolicy::Never => false,
+        };
+
+        let escape = csv.escape.unwrap_or(csv.quote);
+        let escaped_quote = [escape, csv.quote];
+        let escaped_escape = [escape, escape];
+        let escaped_delimiter = [escape, csv.delimiter];
+        let rules: &[(u8, EscapeRule<'_>)] = match (quoted, csv.escape, self.0.escape_backslash) {
+            (true, None, false) => &[(csv.quote, EscapeRule::Escape(&escaped_quote))],
+            (true, None, true) => &[
+                (csv.quote, EscapeRule::Escape(&escaped_quote)),
+                (b'\\', EscapeRule::Escape(br"\\")),
+            ],
+            (true, Some(_), _) => &[
+                (csv.quote, EscapeRule::Escape(&escaped_quote)),
+                (escape, EscapeRule::Escape(&escaped_escape)),
+            ],
+            (false, Some(_), _) => &[
+                (csv.delimiter, EscapeRule::Escape(&escaped_delimiter)),
+                (csv.quote, EscapeRule::Escape(&escaped_quote)),
+                (escape, EscapeRule::Escape(&escaped_escape)),
+                (b'\r', EscapeRule::Escape(&[escape, b'\r'])),
+                (b'\n', EscapeRule::Escape(&[escape, b'\n'])),
+            ],
+            (false, None, true) => &[(b'\\', EscapeRule::Escape(br"\\"))],
+            (false, None, false) => &[],
+        };
+
+        if quoted {
+            writer.write_all(slice::from_ref(&csv.quote))?;
+        }
+        write_with_escape(writer, bytes, rules)?;
+        if quoted {
+            writer.write_all(slice::from_ref(&csv.quote))?;
+        }
+        Ok(())
     }
 
-    fn write_column_name(&self, writer: &mut dyn Write, name: &[u8]) -> Result<(), Error> {
-        writer.write_all(b"\"")?;
-        let (mut rules, name) = match name.first() {
-            Some(b'"') => (Vec::new(), &name[1..(name.len() - 1)]),
-            Some(b'`') => (
-                vec![(b'`', EscapeRule::Unescape(b'`')), (b'"', EscapeRule::Escape(b"\"\""))],
-                &name[1..(name.len() - 1)],
-            ),
-            Some(b'[') => (vec![(b'"', EscapeRule::Escape(b"\"\""))], &name[1..(name.len() - 1)]),
-            _ => (Vec::new(), name),
+    /// Returns the quote to enclose a number, timestamp or interval value.
+    fn scalar_quote(&self, is_numeric: bool) -> &[u8] {
+        let quoted = match self.0.csv.quote_policy {
+            CsvQuotePolicy::Always => true,
+            CsvQuotePolicy::NonNumeric => !is_numeric,
+            CsvQuotePolicy::Strings | CsvQuotePolicy::WhenNeeded | CsvQuotePolicy::Never => false,
         };
-        if self.0.escape_backslash {
-            rules.push((b'\\', EscapeRule::Escape(br"\\")));
+        if quoted {
+            slice::from_ref(&self.0.csv.quote)
+        } else {
+            &[]
+        }
+    }
+
+    /// Writes an array as `{item,item,...}`, where strings are always enclosed in double quotes.
+    fn write_array_element(&self, writer: &mut dyn Write, value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Null => writer.write_all(self.0.null_string.as_bytes()),
+            Value::Number(number) => number.write_io(writer, &self.0.true_string, &self.0.false_string),
+            Value::Bytes(bytes) => {
+                writer.write_all(b"\"")?;
+                write_with_escape(
+                    writer,
+                    bytes.as_bytes(),
+                    if self.0.escape_backslash {
+                        &[(b'"', EscapeRule::Escape(b"\"\"")), (b'\\', EscapeRule::Escape(br"\\"))]
+                    } else {
+                        &[(b'"', EscapeRule::Escape(b"\"\""))]
+                    },
+                )?;
+                writer.write_all(b"\"")
+            }
+            Value::Timestamp(timestamp) => write_timestamp(writer, "", ' ', timestamp),
+            Value::Interval(interval) => write_interval(writer, "", *interval),
+            Value::Array(array) => {
+                writer.write_all(b"{")?;
+                for (i, item) in array.iter().enumerate() {
+                    if i != 0 {
+                        writer.write_all(b",")?;
+                    }
+                    self.write_array_element(writer, &item)?;
+                }
+                writer.write_all(b"}")
+            }
         }
-        write_with_escape(writer, name, &rules)?;
-        writer.write_all(b"\"")
     }
 }
 
@@ -370,22 +884,278 @@ impl Format for CsvFormat<'_> {
     fn write_value(&self, writer: &mut dyn Write, value: &Value) -> Result<(), Error> {
         match value {
             Value::Null => writer.write_all(self.0.null_string.as_bytes()),
+            Value::Number(number) => {
+                let quote = self.scalar_quote(true);
+                writer.write_all(quote)?;
+                number.write_io(writer, &self.0.true_string, &self.0.false_string)?;
+                writer.write_all(quote)
+            }
+            Value::Timestamp(timestamp) => {
+                let quote = self.scalar_quote(false);
+                writer.write_all(quote)?;
+                write_timestamp(writer, "", ' ', timestamp)?;
+                writer.write_all(quote)
+            }
+            Value::Interval(interval) => {
+                let quote = self.scalar_quote(false);
+                writer.write_all(quote)?;
+                write_interval(writer, "", *interval)?;
+                writer.write_all(quote)
+            }
+            Value::Bytes(bytes) => self.write_field(writer, bytes.as_bytes(), true),
+            Value::Array(_) => {
+                let mut buf = Vec::new();
+                self.write_array_element(&mut buf, value)?;
+                self.write_field(writer, &buf, false)
+            }
+        }
+    }
+
+    fn write_file_header(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
+        if self.0.csv.bom {
+            writer.write_all("\u{feff}".as_bytes())?;
+        }
+        if !self.0.headers {
+            return Ok(());
+        }
+        for (i, col) in schema.column_names().enumerate() {
+            if i != 0 {
+                self.write_value_separator(writer)?;
+            }
+            self.write_field(writer, unquote_column_name(col).as_bytes(), true)?;
+        }
+        self.write_row_separator(writer, schema)
+    }
+
+    fn write_header(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_value_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        writer.write_all(slice::from_ref(&self.0.csv.delimiter))
+    }
+
+    fn write_value_header(&self, _: &mut dyn Write, _: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_row_separator(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        writer.write_all(self.0.csv.line_terminator.as_bytes())
+    }
+
+    fn write_trailer(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        writer.write_all(self.0.csv.line_terminator.as_bytes())
+    }
+
+    fn write_transaction_separator(&self, _: &mut dyn Write) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_file_trailer(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Removes the quotation marks around a column name written in the template.
+///
+/// Names quoted with `"…"`, `` `…` `` or `[…]` have their quotes removed and any doubled quote
+/// characters inside unescaped. Unquoted names are returned as-is.
+pub(crate) fn unquote_column_name(name: &str) -> Cow<'_, str> {
+    let (quote, inner) = match name.as_bytes().first() {
+        Some(b'"') => ("\"", &name[1..(name.len() - 1)]),
+        Some(b'`') => ("`", &name[1..(name.len() - 1)]),
+        Some(b'[') => return Cow::Borrowed(&name[1..(name.len() - 1)]),
+        _ => return Cow::Borrowed(name),
+    };
+    if inner.contains(quote) {
+        Cow::Owned(inner.replace(&quote.repeat(2), quote))
+    } else {
+        Cow::Borrowed(inner)
+    }
+}
+
+/// Writes a UTF-8 string as a quoted JSON string.
+fn write_json_string(writer: &mut dyn Write, bytes: &[u8]) -> Result<(), Error> {
+    writer.write_all(b"\"")?;
+    let mut prev_end = 0;
+    for (cur, b) in bytes.iter().enumerate() {
+        if !matches!(b, b'"' | b'\\' | 0..=0x1f) {
+            continue;
+        }
+        writer.write_all(&bytes[prev_end..cur])?;
+        match b {
+            b'"' => writer.write_all(br#"\""#),
+            b'\\' => writer.write_all(br"\\"),
+            b'\n' => writer.write_all(br"\n"),
+            b'\r' => writer.write_all(br"\r"),
+            b'\t' => writer.write_all(br"\t"),
+            _ => write!(writer, "\\u{b:04x}"),
+        }?;
+        prev_end = cur + 1;
+    }
+    writer.write_all(&bytes[prev_end..])?;
+    writer.write_all(b"\"")
+}
+
+impl Format for JsonLinesFormat<'_> {
+    /// Writes a value in JSON format.
+    ///
+    /// Timestamps are written as ISO 8601 strings, and intervals are written as strings in the
+    /// standard SQL format. Byte strings which are not valid UTF-8 are encoded in base64. NULL and
+    /// booleans are always written as JSON literals regardless of the configured strings.
+    fn write_value(&self, writer: &mut dyn Write, value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Null => writer.write_all(b"null"),
+            Value::Number(number) => number.write_io(writer, "true", "false"),
+            Value::Bytes(bytes) => {
+                if bytes.encoding() == Encoding::Binary {
+                    write!(writer, "\"{}\"", BASE64.encode(bytes.as_bytes()))
+                } else {
+                    write_json_string(writer, bytes.as_bytes())
+                }
+            }
+            Value::Timestamp(timestamp) => write_timestamp(writer, "\"", 'T', timestamp),
+            Value::Interval(interval) => write_interval(writer, "\"", *interval),
+            Value::Array(array) => {
+                writer.write_all(b"[")?;
+                for (i, item) in array.iter().enumerate() {
+                    if i != 0 {
+                        writer.write_all(b",")?;
+                    }
+                    self.write_value(writer, &item)?;
+                }
+                writer.write_all(b"]")
+            }
+        }
+    }
+
+    fn write_file_header(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_header(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        writer.write_all(b"{")
+    }
+
+    fn write_value_header(&self, writer: &mut dyn Write, column: &str) -> Result<(), Error> {
+        write_json_string(writer, unquote_column_name(column).as_bytes())?;
+        writer.write_all(b":")
+    }
+
+    fn write_value_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        writer.write_all(b",")
+    }
+
+    fn write_row_separator(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        writer.write_all(b"}\n{")
+    }
+
+    fn write_trailer(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        writer.write_all(b"}\n")
+    }
+
+    fn write_transaction_separator(&self, _: &mut dyn Write) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_file_trailer(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Writes a time interval which PostgreSQL would parse back to the same value.
+fn write_pg_interval(writer: &mut dyn Write, interval: i64) -> Result<(), Error> {
+    // PostgreSQL only applies the leading minus sign of `-1 02:03:04` to the day part,
+    // so negative intervals are written as plain number of microseconds.
+    if interval < 0 {
+        write!(writer, "{interval} microseconds")
+    } else {
+        write_interval(writer, "", interval)
+    }
+}
+
+/// The escape rules of the PostgreSQL `COPY` text format.
+const PG_COPY_ESCAPE_RULES: &[(u8, EscapeRule<'static>)] = &[
+    (b'\\', EscapeRule::Escape(br"\\")),
+    (b'\t', EscapeRule::Escape(br"\t")),
+    (b'\n', EscapeRule::Escape(br"\n")),
+    (b'\r', EscapeRule::Escape(br"\r")),
+];
+
+impl PgCopyFormat<'_> {
+    /// Writes a value as an element of a PostgreSQL array literal.
+    fn write_array_element(&self, writer: &mut dyn Write, value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Null => writer.write_all(b"NULL"),
             Value::Number(number) => number.write_io(writer, &self.0.true_string, &self.0.false_string),
-            Value::Bytes(bytes) => self.write_bytes(writer, bytes),
-            Value::Timestamp(timestamp) => write_timestamp(writer, "", timestamp),
-            Value::Interval(interval) => write_interval(writer, "", *interval),
+            Value::Bytes(bytes) => {
+                writer.write_all(b"\"")?;
+                if bytes.encoding() == Encoding::Binary {
+                    writer.write_all(br"\\x")?;
+                    for b in bytes.as_bytes() {
+                        write!(writer, "{b:02x}")?;
+                    }
+                } else {
+                    write_with_escape(
+                        writer,
+                        bytes.as_bytes(),
+                        &[
+                            (b'"', EscapeRule::Escape(br#"\""#)),
+                            (b'\\', EscapeRule::Escape(br"\\")),
+                        ],
+                    )?;
+                }
+                writer.write_all(b"\"")
+            }
+            Value::Timestamp(timestamp) => write_timestamp(writer, "\"", ' ', timestamp),
+            Value::Interval(interval) => {
+                writer.write_all(b"\"")?;
+                write_pg_interval(writer, *interval)?;
+                writer.write_all(b"\"")
+            }
             Value::Array(array) => {
                 writer.write_all(b"{")?;
                 for (i, item) in array.iter().enumerate() {
                     if i != 0 {
                         writer.write_all(b",")?;
                     }
-                    self.write_value(writer, &item)?;
+                    self.write_array_element(writer, &item)?;
                 }
                 writer.write_all(b"}")
             }
         }
     }
+}
+
+impl Format for PgCopyFormat<'_> {
+    /// Writes a value in the text format of PostgreSQL `COPY`.
+    ///
+    /// Byte strings which are not valid UTF-8 are written in the hex format of `bytea`, and
+    /// arrays are written as PostgreSQL array literals.
+    fn write_value(&self, writer: &mut dyn Write, value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Null => writer.write_all(self.0.null_string.as_bytes()),
+            Value::Number(number) => number.write_io(writer, &self.0.true_string, &self.0.false_string),
+            Value::Bytes(bytes) => {
+                if bytes.encoding() == Encoding::Binary {
+                    writer.write_all(br"\\x")?;
+                    for b in bytes.as_bytes() {
+                        write!(writer, "{b:02x}")?;
+                    }
+                    Ok(())
+                } else {
+                    write_with_escape(writer, bytes.as_bytes(), PG_COPY_ESCAPE_RULES)
+                }
+            }
+            Value::Timestamp(timestamp) => write_timestamp(writer, "", ' ', timestamp),
+            Value::Interval(interval) => write_pg_interval(writer, *interval),
+            Value::Array(_) => {
+                let mut literal = Vec::new();
+                self.write_array_element(&mut literal, value)?;
+                write_with_escape(writer, &literal, PG_COPY_ESCAPE_RULES)
+            }
+        }
+    }
 
     fn write_file_header(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
         if !self.0.headers {
@@ -395,30 +1165,150 @@ impl Format for CsvFormat<'_> {
             if i != 0 {
                 self.write_value_separator(writer)?;
             }
-            self.write_column_name(writer, col.as_bytes())?;
+            write_with_escape(writer, unquote_column_name(col).as_bytes(), PG_COPY_ESCAPE_RULES)?;
         }
-        self.write_row_separator(writer)
+        self.write_row_separator(writer, schema)
     }
 
     fn write_header(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
         Ok(())
     }
 
+    fn write_value_header(&self, _: &mut dyn Write, _: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
     fn write_value_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
-        writer.write_all(b",")
+        writer.write_all(b"\t")
+    }
+
+    fn write_row_separator(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        writer.write_all(b"\n")
+    }
+
+    fn write_trailer(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        writer.write_all(b"\n")
+    }
+
+    fn write_transaction_separator(&self, _: &mut dyn Write) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_file_trailer(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// The escape rules of the default MySQL `LOAD DATA` format.
+const MYSQL_TSV_ESCAPE_RULES: &[(u8, EscapeRule<'static>)] = &[
+    (b'\\', EscapeRule::Escape(br"\\")),
+    (b'\t', EscapeRule::Escape(br"\t")),
+    (b'\n', EscapeRule::Escape(br"\n")),
+    (b'\0', EscapeRule::Escape(br"\0")),
+];
+
+impl MySqlTsvFormat<'_> {
+    /// Writes the `LOAD DATA` statement which loads the file at `path` into the table.
+    ///
+    /// The statement reads the file as the `binary` character set, so that byte strings are
+    /// inserted without conversion.
+    pub fn write_load_data_statement(
+        &self,
+        writer: &mut dyn Write,
+        schema: &Schema<'_>,
+        path: &str,
+    ) -> Result<(), Error> {
+        writer.write_all(b"LOAD DATA LOCAL INFILE '")?;
+        write_with_escape(
+            writer,
+            path.as_bytes(),
+            &[(b'\'', EscapeRule::Escape(br"\'")), (b'\\', EscapeRule::Escape(br"\\"))],
+        )?;
+        write!(
+            writer,
+            "' INTO TABLE {} CHARACTER SET binary FIELDS TERMINATED BY '\\t' ESCAPED BY '\\\\' LINES TERMINATED BY '\\n' ",
+            schema.name
+        )?;
+        if self.0.headers {
+            writer.write_all(b"IGNORE 1 LINES ")?;
+        }
+        writer.write_all(b"(")?;
+        for (i, col) in schema.column_names().enumerate() {
+            if i != 0 {
+                writer.write_all(b", ")?;
+            }
+            writer.write_all(b"`")?;
+            write_with_escape(
+                writer,
+                unquote_column_name(col).as_bytes(),
+                &[(b'`', EscapeRule::Escape(b"``"))],
+            )?;
+            writer.write_all(b"`")?;
+        }
+        writer.write_all(b");\n")
+    }
+}
+
+impl Format for MySqlTsvFormat<'_> {
+    /// Writes a value in the default format of MySQL `LOAD DATA`.
+    ///
+    /// Byte strings are written as-is apart from the escape sequences, and arrays are written as
+    /// JSON arrays.
+    fn write_value(&self, writer: &mut dyn Write, value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Null => writer.write_all(self.0.null_string.as_bytes()),
+            Value::Number(number) => number.write_io(writer, &self.0.true_string, &self.0.false_string),
+            Value::Bytes(bytes) => write_with_escape(writer, bytes.as_bytes(), MYSQL_TSV_ESCAPE_RULES),
+            Value::Timestamp(timestamp) => write_timestamp(writer, "", ' ', timestamp),
+            Value::Interval(interval) => write_interval(writer, "", *interval),
+            Value::Array(_) => {
+                let mut json = Vec::new();
+                JsonLinesFormat(self.0).write_value(&mut json, value)?;
+                write_with_escape(writer, &json, MYSQL_TSV_ESCAPE_RULES)
+            }
+        }
+    }
+
+    fn write_file_header(&self, writer: &mut dyn Write, schema: &Schema<'_>) -> Result<(), Error> {
+        if !self.0.headers {
+            return Ok(());
+        }
+        for (i, col) in schema.column_names().enumerate() {
+            if i != 0 {
+                self.write_value_separator(writer)?;
+            }
+            write_with_escape(writer, unquote_column_name(col).as_bytes(), MYSQL_TSV_ESCAPE_RULES)?;
+        }
+        self.write_row_separator(writer, schema)
+    }
+
+    fn write_header(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        Ok(())
     }
 
     fn write_value_header(&self, _: &mut dyn Write, _: &str) -> Result<(), Error> {
         Ok(())
     }
 
-    fn write_row_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
+    fn write_value_separator(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        writer.write_all(b"\t")
+    }
+
+    fn write_row_separator(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
         writer.write_all(b"\n")
     }
 
-    fn write_trailer(&self, writer: &mut dyn Write) -> Result<(), Error> {
+    fn write_trailer(&self, writer: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
         writer.write_all(b"\n")
     }
+
+    fn write_transaction_separator(&self, _: &mut dyn Write) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn write_file_trailer(&self, _: &mut dyn Write, _: &Schema<'_>) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -426,8 +1316,10 @@ mod tests {
     use super::*;
 
     #[test]
+    // ALLOW_REASON: the test cases are written as tuples for brevity.
+    #[allow(clippy::type_complexity)]
     fn test_write_with_escape() {
-        let test_cases: Vec<(&[u8], &[(u8, EscapeRule)], &[u8])> = vec![
+        let test_cases: Vec<(&[u8], &[(u8, EscapeRule<'_>)], &[u8])> = vec![
             (b"10 o'clock", &[], b"10 o'clock"),
             (b"10 o'clock", &[(b'\'', EscapeRule::Escape(b"''"))], b"10 o''clock"),
             (
@@ -449,11 +1341,6 @@ mod tests {
                 ],
                 b"&lt;b&gt;&quot;R&amp;D&quot;&lt;/b&gt;",
             ),
-            (
-                b"`a'b``c`",
-                &[(b'\'', EscapeRule::Escape(b"''")), (b'`', EscapeRule::Unescape(b'`'))],
-                b"`a''b`c`",
-            ),
         ];
 
         for (src, rules, expected) in test_cases {
@@ -462,4 +1349,114 @@ mod tests {
             assert_eq!(&writer, expected);
         }
     }
+
+    #[test]
+    fn test_split_qualified_name() {
+        let test_cases: Vec<(&str, Vec<&str>)> = vec![
+            ("t", vec!["t"]),
+            ("db.schema.t", vec!["db", "schema", "t"]),
+            (r#""a.b"."c"".d""#, vec![r#""a.b""#, r#""c"".d""#]),
+            ("`x.y`.[p.q]]]", vec!["`x.y`", "[p.q]]]"]),
+        ];
+        for (name, expected) in test_cases {
+            assert_eq!(split_qualified_name(name).collect::<Vec<_>>(), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_json_lines_literals() {
+        let options = Options {
+            true_string: Cow::Borrowed("TRUE"),
+            false_string: Cow::Borrowed("FALSE"),
+            null_string: Cow::Borrowed(r"\N"),
+            ..Options::default()
+        };
+        let value = Value::Array(crate::array::Array::from_values(vec![
+            Value::Null,
+            Value::from(true),
+            Value::from(false),
+            Value::from(1),
+        ]));
+        let mut json = Vec::new();
+        JsonLinesFormat(&options).write_value(&mut json, &value).unwrap();
+        assert_eq!(json, b"[null,true,false,1]");
+    }
+
+    #[test]
+    fn test_write_sql_value_dialects() {
+        let values = [
+            Value::Bytes(ByteString::from(r"it's a\b".to_owned())),
+            Value::Bytes(ByteString::from(vec![0x00, 0xab])),
+            Value::Timestamp(NaiveDateTime::parse_from_str("2001-04-19 18:08:51.25", "%Y-%m-%d %H:%M:%S%.f").unwrap()),
+            Value::Interval(-90_061_000_001),
+        ];
+        let test_cases = [
+            (
+                None,
+                [
+                    r"'it''s a\b'",
+                    "X'00AB'",
+                    "'2001-04-19 18:08:51.250000'",
+                    "'-1 01:01:01.000001'",
+                ],
+            ),
+            (
+                Some(Dialect::MySQL),
+                [
+                    r"'it''s a\\b'",
+                    "X'00AB'",
+                    "'2001-04-19 18:08:51.250000'",
+                    "'-1 01:01:01.000001'",
+                ],
+            ),
+            (
+                Some(Dialect::PostgreSQL),
+                [
+                    r"'it''s a\b'",
+                    r"'\x00AB'::bytea",
+                    "TIMESTAMP '2001-04-19 18:08:51.250000'",
+                    "INTERVAL '-90061000001 microseconds'",
+                ],
+            ),
+            (
+                Some(Dialect::SQLite),
+                [
+                    r"'it''s a\b'",
+                    "X'00AB'",
+                    "'2001-04-19 18:08:51.250000'",
+                    "'-1 01:01:01.000001'",
+                ],
+            ),
+            (
+                Some(Dialect::SQLServer),
+                [
+                    r"N'it''s a\b'",
+                    "0x00AB",
+                    "CAST('2001-04-19T18:08:51.250000' AS DATETIME2)",
+                    "'-1 01:01:01.000001'",
+                ],
+            ),
+            (
+                Some(Dialect::Oracle),
+                [
+                    r"'it''s a\b'",
+                    "HEXTORAW('00AB')",
+                    "TIMESTAMP '2001-04-19 18:08:51.250000'",
+                    "INTERVAL '-1 01:01:01.000001' DAY(9) TO SECOND(6)",
+                ],
+            ),
+        ];
+
+        for (dialect, expected) in test_cases {
+            let options = Options {
+                dialect,
+                ..Options::default()
+            };
+            for (value, expected) in values.iter().zip(expected) {
+                let mut writer = Vec::new();
+                options.write_sql_value(&mut writer, value).unwrap();
+                assert_eq!(String::from_utf8(writer).unwrap(), expected, "{dialect:?}");
+            }
+        }
+    }
 }
@@ -258,7 +258,7 @@ mod tests {
         let ctx = CompileContext::new(0);
         let span = Span::default();
         for tc in test_cases {
-            let args = tc.args.into_iter().map(|v| v.no_span()).collect();
+            let args = tc.args.into_iter().map(SpanExt::no_span).collect();
             let actual_error = tc
                 .function
                 .compile(&ctx, span, args)
@@ -53,6 +53,8 @@ impl Unit {
 }
 
 #[test]
+// ALLOW_REASON: the test is just a long list of assertions.
+#[allow(clippy::cognitive_complexity)]
 fn test_parse_sql_range() {
     let b = ByteString::from("123456789".to_owned());
     for unit in &[Unit::Octets, Unit::Characters] {
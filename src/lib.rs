@@ -83,16 +83,41 @@ pub const FULL_VERSION: &str = concat!(
 pub mod array;
 pub mod bytes;
 #[cfg(feature = "cli")]
+pub mod checkpoint;
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod error;
+#[cfg(feature = "cli")]
+pub mod estimate;
 pub mod eval;
 pub mod format;
 pub mod functions;
 pub mod lexctr;
+#[cfg(feature = "cli")]
+pub mod manifest;
+#[cfg(feature = "mysql")]
+pub mod mysql;
 pub mod number;
+#[cfg(feature = "cli")]
+pub mod parallel_compress;
+#[cfg(feature = "parquet")]
+pub mod parquet;
 pub mod parser;
 #[cfg(feature = "cli")]
+pub mod partition;
+#[cfg(feature = "cli")]
+pub mod pending_file;
+pub mod pg_copy;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "s3")]
+pub mod s3;
+#[cfg(feature = "cli")]
 pub mod schemagen_cli;
 pub mod span;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "cli")]
+pub mod stream;
 pub mod value;
 pub mod writer;
@@ -0,0 +1,57 @@
+//! The `manifest.json` file listing every data file produced by a run.
+//!
+//! The manifest records the version of dbgen, the seed and the arguments of the run, so a reader
+//! can check that a set of files is complete and reproduce them.
+
+use crate::cli::{RngName, Seed};
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+/// The content of `manifest.json`.
+#[derive(Debug, Serialize)]
+pub struct Manifest<'a> {
+    /// The full version of dbgen.
+    pub version: &'a str,
+    /// The seed of the random number generators.
+    pub seed: Seed,
+    /// The random number generator engine.
+    pub rng: RngName,
+    /// The command line arguments.
+    pub args: serde_json::Value,
+    /// The data files.
+    pub files: Vec<ManifestFile<'a>>,
+}
+
+/// A data file listed in `manifest.json`.
+#[derive(Debug, Serialize)]
+pub struct ManifestFile<'a> {
+    /// Name of the table.
+    pub table: &'a str,
+    /// Index of the file generator thread which produced the file.
+    pub file_index: u32,
+    /// Path of the file relative to the output directory.
+    pub path: Cow<'a, str>,
+    /// The `rownum` of the first row, or `None` if the file is empty.
+    pub first_rownum: Option<u64>,
+    /// The `rownum` of the last row, or `None` if the file is empty.
+    pub last_rownum: Option<u64>,
+    /// Number of rows in the file.
+    pub rows_count: u64,
+    /// Size of the file before compression.
+    pub uncompressed_size: u64,
+    /// Size of the file on disk.
+    pub compressed_size: u64,
+    /// The SHA-256 checksum of the file on disk, in hex.
+    pub sha256: &'a str,
+}
+
+impl Manifest<'_> {
+    /// Writes the manifest as pretty-printed JSON.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()
+    }
+}
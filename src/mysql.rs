@@ -0,0 +1,234 @@
+//! Loading rows directly into a MySQL or `TiDB` database.
+//!
+//! Instead of producing data files, the generated INSERT statements (or the rows encoded in the
+//! `mysql-tsv` format, through `LOAD DATA LOCAL INFILE`) are executed in the server. Every
+//! generator thread uses its own connection, and every batch is executed in its own transaction,
+//! so a batch failing with a transient error can be retried as a whole.
+
+use crate::error::Error;
+use mysql::{Conn, LocalInfileHandler, Opts, TxOpts, prelude::Queryable as _};
+use std::{
+    io::{self, Write},
+    str,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread::sleep,
+    time::Duration,
+};
+
+/// Server error codes which are worth retrying.
+const TRANSIENT_ERROR_CODES: &[u16] = &[
+    1040, // ER_CON_COUNT_ERROR (too many connections)
+    1205, // ER_LOCK_WAIT_TIMEOUT
+    1213, // ER_LOCK_DEADLOCK
+    8022, // TiDB: retryable transaction error
+    8028, // TiDB: information schema is changed
+    9001, // TiDB: PD server timeout
+    9002, // TiDB: TiKV server timeout
+    9003, // TiDB: TiKV server is busy
+    9004, // TiDB: resolve lock timeout
+    9005, // TiDB: region is unavailable
+    9007, // TiDB: write conflict
+];
+
+/// Checks whether an error is transient, i.e. executing the same batch again may succeed.
+fn is_transient(e: &mysql::Error) -> bool {
+    match e {
+        mysql::Error::IoError(_) | mysql::Error::CodecError(_) => true,
+        mysql::Error::MySqlError(e) => TRANSIENT_ERROR_CODES.contains(&e.code),
+        _ => false,
+    }
+}
+
+/// A table receiving rows.
+#[derive(Debug)]
+struct InsertTarget {
+    /// The table name.
+    name: String,
+    /// The `LOAD DATA LOCAL INFILE` statement, if the rows are encoded in the `mysql-tsv` format
+    /// instead of as INSERT statements.
+    load_data_statement: Option<String>,
+    /// Number of rows inserted into the table so far, as reported by the server.
+    rows_count: AtomicU64,
+}
+
+/// A MySQL database shared by all writers.
+#[derive(Debug)]
+pub struct MySqlDatabase {
+    /// The parsed connection URL.
+    opts: Opts,
+    /// SQL statements executed on every new connection.
+    session_init: Option<String>,
+    /// Maximum number of times a batch failing with a transient error is retried.
+    max_retries: u32,
+    /// The connection of each generator thread, opened on first use.
+    connections: Vec<Mutex<Option<Conn>>>,
+    /// The tables receiving the rows.
+    targets: Vec<InsertTarget>,
+}
+
+impl MySqlDatabase {
+    /// Connects to the database at the given URL.
+    ///
+    /// `threads_count` is the number of generator threads. `tables` are the names of all tables,
+    /// together with the `LOAD DATA LOCAL INFILE` statement if the rows are encoded in the
+    /// `mysql-tsv` format.
+    pub fn connect(
+        url: &str,
+        threads_count: usize,
+        tables: impl IntoIterator<Item = (String, Option<String>)>,
+        session_init: Option<String>,
+        max_retries: u32,
+    ) -> Result<Self, Error> {
+        let mut database = Self {
+            opts: Opts::from_url(url).map_err(mysql::Error::from)?,
+            session_init,
+            max_retries,
+            connections: (0..threads_count.max(1)).map(|_| Mutex::new(None)).collect(),
+            targets: tables
+                .into_iter()
+                .map(|(name, load_data_statement)| InsertTarget {
+                    name,
+                    load_data_statement,
+                    rows_count: AtomicU64::new(0),
+                })
+                .collect(),
+        };
+        // Connect once here, so an unreachable server is reported before generating anything.
+        let conn = database.open_connection()?;
+        *database.connections[0]
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner) = Some(conn);
+        Ok(database)
+    }
+
+    /// Opens a new connection and initializes the session.
+    fn open_connection(&self) -> Result<Conn, mysql::Error> {
+        let mut conn = Conn::new(self.opts.clone())?;
+        if let Some(session_init) = &self.session_init {
+            conn.query_drop(session_init)?;
+        }
+        Ok(conn)
+    }
+
+    /// Locks the connection of the current generator thread, opening it if necessary.
+    fn lock_connection(&self) -> Result<MutexGuard<'_, Option<Conn>>, mysql::Error> {
+        let index = rayon::current_thread_index().unwrap_or(0) % self.connections.len();
+        let mut connection = self.connections[index].lock().unwrap_or_else(PoisonError::into_inner);
+        if connection.is_none() {
+            *connection = Some(self.open_connection()?);
+        }
+        Ok(connection)
+    }
+
+    /// Executes SQL statements separated by semicolons, e.g. `CREATE TABLE` statements.
+    pub fn execute_batch(&self, sql: &str) -> Result<(), Error> {
+        let mut connection = self.lock_connection()?;
+        let conn = connection.as_mut().expect("connection should be opened");
+        conn.query_drop(sql)?;
+        Ok(())
+    }
+
+    /// Executes a batch of INSERT statements, or loads a batch of `mysql-tsv` rows, in a single
+    /// transaction. Returns the number of affected rows.
+    fn try_insert(&self, target: &InsertTarget, data: &Arc<[u8]>) -> Result<u64, mysql::Error> {
+        let mut connection = self.lock_connection()?;
+        let conn = connection.as_mut().expect("connection should be opened");
+        let result = (|| {
+            let mut transaction = conn.start_transaction(TxOpts::default())?;
+            let rows_count = if let Some(statement) = &target.load_data_statement {
+                let data = Arc::clone(data);
+                transaction
+                    .set_local_infile_handler(Some(LocalInfileHandler::new(move |_, file| file.write_all(&data))));
+                transaction.query_drop(statement)?;
+                transaction.affected_rows()
+            } else {
+                let mut result = transaction.query_iter(String::from_utf8_lossy(data))?;
+                let mut rows_count = 0;
+                while let Some(result_set) = result.iter() {
+                    rows_count += result_set.affected_rows();
+                    for row in result_set {
+                        row?;
+                    }
+                }
+                rows_count
+            };
+            transaction.commit()?;
+            Ok(rows_count)
+        })();
+        if let Err(mysql::Error::IoError(_) | mysql::Error::CodecError(_)) = result {
+            // The connection is broken, so reconnect on the next attempt.
+            *connection = None;
+        }
+        result
+    }
+
+    /// Inserts a batch into a table, retrying on transient errors.
+    fn insert(&self, table_index: usize, data: &[u8]) -> io::Result<()> {
+        let target = &self.targets[table_index];
+        if target.load_data_statement.is_none() {
+            str::from_utf8(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        let data = Arc::from(data);
+        let mut retries = 0;
+        loop {
+            match self.try_insert(target, &data) {
+                Ok(rows_count) => {
+                    target.rows_count.fetch_add(rows_count, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(e) if retries < self.max_retries && is_transient(&e) => {
+                    sleep(Duration::from_millis(100 << retries.min(6)));
+                    retries += 1;
+                }
+                Err(e) => return Err(io::Error::other(e)),
+            }
+        }
+    }
+
+    /// Returns the name and number of rows inserted of every table.
+    pub fn rows_counts(&self) -> impl Iterator<Item = (&str, u64)> + '_ {
+        self.targets
+            .iter()
+            .map(|target| (&*target.name, target.rows_count.load(Ordering::Relaxed)))
+    }
+}
+
+/// An output buffering rows of a table, which are inserted into the database when flushed.
+#[derive(Debug)]
+pub struct MySqlInsertWriter {
+    /// The target database.
+    database: Arc<MySqlDatabase>,
+    /// Index of the table in the database targets.
+    table_index: usize,
+    /// The rows not yet inserted.
+    buffer: Vec<u8>,
+}
+
+impl MySqlInsertWriter {
+    /// Creates a new writer inserting into the table at the given index.
+    pub fn new(database: Arc<MySqlDatabase>, table_index: usize) -> Self {
+        Self {
+            database,
+            table_index,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl Write for MySqlInsertWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.database.insert(self.table_index, &self.buffer)?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+}
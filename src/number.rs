@@ -14,6 +14,17 @@ enum N {
     F(f64),
 }
 
+/// The kind of value stored in a [`Number`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NumberKind {
+    /// A boolean.
+    Bool,
+    /// An integer.
+    Integer,
+    /// A finite floating-point number.
+    Float,
+}
+
 /// The error returned in numerical arithmetics.
 #[derive(Debug, PartialEq, Eq)]
 #[non_exhaustive]
@@ -112,6 +123,15 @@ impl Number {
         Self(N::F(v))
     }
 
+    /// Returns the kind of value stored in this number.
+    pub fn kind(self) -> NumberKind {
+        match self.0 {
+            N::B(_) => NumberKind::Bool,
+            N::I(_) => NumberKind::Integer,
+            N::F(_) => NumberKind::Float,
+        }
+    }
+
     fn try_as_i128(self) -> Result<i128, f64> {
         match self.0 {
             N::B(v) => Ok(v.into()),
@@ -282,6 +302,8 @@ impl PartialOrd for Number {
 }
 
 #[cfg(test)]
+// ALLOW_REASON: the tests intentionally check the behavior at the limits of floating point precision.
+#[allow(clippy::cast_precision_loss, clippy::unreadable_literal, clippy::lossy_float_literal)]
 mod tests {
     use super::*;
 
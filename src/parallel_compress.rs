@@ -0,0 +1,218 @@
+//! Compressing a single output stream on multiple threads.
+//!
+//! The input is split into blocks which are compressed independently by a pool of worker threads,
+//! and the compressed blocks are written in their original order. Every block becomes a complete
+//! stream of the compression format (e.g. a gzip member), and standard decompressors treat the
+//! concatenation of such streams as a single file, like the output of `pigz --independent`.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, Write},
+    mem,
+    sync::{
+        Arc, Mutex, PoisonError,
+        mpsc::{Receiver, Sender, channel},
+    },
+    thread::{JoinHandle, spawn},
+};
+
+/// Size of the uncompressed input of every block.
+const BLOCK_SIZE: usize = 1 << 20;
+
+/// A block waiting to be compressed by a worker thread.
+struct Job {
+    /// The uncompressed input.
+    input: Vec<u8>,
+    /// Where to send the compressed output.
+    output: Sender<io::Result<Vec<u8>>>,
+}
+
+/// A writer compressing blocks of its input on multiple threads.
+pub struct ParallelCompressor<W: Write> {
+    /// The destination of the compressed output.
+    inner: W,
+    /// The input of the block not yet submitted.
+    block: Vec<u8>,
+    /// Sends the blocks to the worker threads. `None` after the writer is finished.
+    jobs: Option<Sender<Job>>,
+    /// The compressed output of the submitted blocks, in the input order.
+    pending: VecDeque<Receiver<io::Result<Vec<u8>>>>,
+    /// The worker threads.
+    workers: Vec<JoinHandle<()>>,
+    /// Whether any block has been submitted.
+    has_submitted: bool,
+}
+
+impl<W: Write> fmt::Debug for ParallelCompressor<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParallelCompressor")
+            .field("block_len", &self.block.len())
+            .field("pending_count", &self.pending.len())
+            .field("threads_count", &self.workers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write> ParallelCompressor<W> {
+    /// Creates a new writer using `threads_count` worker threads.
+    ///
+    /// The `compress` function must turn a block into a complete stream of the compression format.
+    pub fn new(
+        inner: W,
+        threads_count: usize,
+        compress: impl Fn(&[u8]) -> io::Result<Vec<u8>> + Send + Sync + 'static,
+    ) -> Self {
+        let (jobs, jobs_receiver) = channel::<Job>();
+        let jobs_receiver = Arc::new(Mutex::new(jobs_receiver));
+        let compress = Arc::new(compress);
+        let workers = (0..threads_count.max(1))
+            .map(|_| {
+                let jobs_receiver = Arc::clone(&jobs_receiver);
+                let compress = Arc::clone(&compress);
+                spawn(move || {
+                    loop {
+                        let job = jobs_receiver.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                        let Ok(job) = job else { break };
+                        // The receiver is gone only if the writer is dropped due to another error.
+                        job.output.send(compress(&job.input)).ok();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        Self {
+            inner,
+            block: Vec::with_capacity(BLOCK_SIZE),
+            jobs: Some(jobs),
+            pending: VecDeque::new(),
+            workers,
+            has_submitted: false,
+        }
+    }
+
+    /// Sends the current block to the worker threads.
+    ///
+    /// To bound the memory usage, this waits for the earliest blocks to be written if too many
+    /// blocks are pending.
+    fn submit_block(&mut self) -> io::Result<()> {
+        let input = mem::replace(&mut self.block, Vec::with_capacity(BLOCK_SIZE));
+        let (output, output_receiver) = channel();
+        self.jobs
+            .as_ref()
+            .expect("writer should not be finished")
+            .send(Job { input, output })
+            .map_err(|_| io::Error::other("compression threads have stopped"))?;
+        self.pending.push_back(output_receiver);
+        self.has_submitted = true;
+        while self.pending.len() > 2 * self.workers.len() {
+            self.write_next_block()?;
+        }
+        Ok(())
+    }
+
+    /// Waits for the earliest pending block to be compressed and writes it out.
+    fn write_next_block(&mut self) -> io::Result<()> {
+        if let Some(output_receiver) = self.pending.pop_front() {
+            let output = output_receiver
+                .recv()
+                .map_err(|_| io::Error::other("compression thread panicked"))??;
+            self.inner.write_all(&output)?;
+        }
+        Ok(())
+    }
+
+    /// Writes out all blocks submitted so far.
+    fn write_pending_blocks(&mut self) -> io::Result<()> {
+        while !self.pending.is_empty() {
+            self.write_next_block()?;
+        }
+        Ok(())
+    }
+
+    /// Returns a mutable reference to the destination of the compressed output.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Compresses the remaining input, writes out everything and stops the worker threads.
+    pub fn try_finish(&mut self) -> io::Result<()> {
+        if self.jobs.is_none() {
+            return Ok(());
+        }
+        // An empty input still needs one (empty) stream to produce a valid file.
+        if !self.block.is_empty() || !self.has_submitted {
+            self.submit_block()?;
+        }
+        self.write_pending_blocks()?;
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            worker
+                .join()
+                .map_err(|_| io::Error::other("compression thread panicked"))?;
+        }
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Write for ParallelCompressor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(BLOCK_SIZE - self.block.len());
+        self.block.extend_from_slice(&buf[..len]);
+        if self.block.len() >= BLOCK_SIZE {
+            self.submit_block()?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.block.is_empty() {
+            self.submit_block()?;
+        }
+        self.write_pending_blocks()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for ParallelCompressor<W> {
+    fn drop(&mut self) {
+        // Errors cannot be reported from `drop()`, same as `GzEncoder` and `XzEncoder`.
+        self.try_finish().ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compression, read::MultiGzDecoder, write::GzEncoder};
+    use std::io::Read;
+
+    fn gzip(input: &[u8]) -> io::Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(input)?;
+        encoder.finish()
+    }
+
+    fn round_trip(input: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        {
+            let mut writer = ParallelCompressor::new(&mut compressed, 3, gzip);
+            for chunk in input.chunks(12345) {
+                writer.write_all(chunk).unwrap();
+            }
+            writer.try_finish().unwrap();
+        }
+        let mut output = Vec::new();
+        MultiGzDecoder::new(&*compressed).read_to_end(&mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_parallel_gzip() {
+        let input = (0..1_000_000_u32)
+            .flat_map(|i| (i % 251).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(round_trip(&input), input);
+        assert_eq!(round_trip(b"hello"), b"hello");
+        assert_eq!(round_trip(b""), b"");
+    }
+}
@@ -0,0 +1,485 @@
+//! Parquet output.
+//!
+//! Unlike the textual formats in [`crate::format`], Parquet is a columnar format, so the values
+//! cannot be streamed out one by one. Instead, [`ParquetWriter`] collects every row of an INSERT
+//! statement and writes them out as a single row group when the statement is concluded.
+
+use crate::{
+    error::Error,
+    eval::Schema,
+    format::{unquote_column_name, write_interval, write_timestamp},
+    number::NumberKind,
+    span::{ResultExt, S},
+    value::Value,
+    writer::Writer,
+};
+use ::parquet::{
+    basic::{Compression, ConvertedType, LogicalType, Repetition, TimeUnit, Type as PhysicalType},
+    data_type::{
+        BoolType, ByteArray, ByteArrayType, DataType, DoubleType, FixedLenByteArray, FixedLenByteArrayType, Int64Type,
+    },
+    file::{
+        properties::{WriterProperties, WriterPropertiesPtr},
+        writer::{SerializedColumnWriter, SerializedFileWriter},
+    },
+    schema::types::{Type, TypePtr},
+};
+use rand_regex::Encoding;
+use std::{io::Write, mem, sync::Arc};
+
+/// The Parquet column type chosen for a table column.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ColumnType {
+    /// `BOOLEAN`.
+    Boolean,
+    /// `INT64`.
+    Int64,
+    /// `DOUBLE`.
+    Double,
+    /// `BYTE_ARRAY` annotated as a UTF-8 string.
+    String,
+    /// `BYTE_ARRAY` without annotation.
+    Binary,
+    /// `INT64` annotated as a timestamp in microseconds.
+    Timestamp,
+    /// `FIXED_LEN_BYTE_ARRAY(12)` annotated as an interval of months, days and milliseconds.
+    Interval,
+}
+
+impl ColumnType {
+    /// Determines the column type from the declared type in the `CREATE TABLE` statement.
+    ///
+    /// Types which are not recognized are stored as strings.
+    fn of_declared_type(ty: &str) -> Self {
+        let ty = ty.to_ascii_lowercase();
+        let name = ty
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .next()
+            .unwrap_or_default();
+        match name {
+            "bool" | "boolean" => Self::Boolean,
+            "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "int2" | "int4" | "int8"
+            | "serial" | "bigserial" | "smallserial" => Self::Int64,
+            "real" | "float" | "float4" | "float8" | "double" | "decimal" | "numeric" => Self::Double,
+            "timestamp" | "datetime" => Self::Timestamp,
+            "interval" => Self::Interval,
+            "binary" | "varbinary" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "bytea" => Self::Binary,
+            _ => Self::String,
+        }
+    }
+
+    /// Constructs the Parquet schema type of a column with this type.
+    fn to_parquet_type(self, name: &str) -> Result<TypePtr, Error> {
+        let (physical_type, logical_type, converted_type) = match self {
+            Self::Boolean => (PhysicalType::BOOLEAN, None, ConvertedType::NONE),
+            Self::Int64 => (PhysicalType::INT64, None, ConvertedType::NONE),
+            Self::Double => (PhysicalType::DOUBLE, None, ConvertedType::NONE),
+            Self::String => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String), ConvertedType::NONE),
+            Self::Binary => (PhysicalType::BYTE_ARRAY, None, ConvertedType::NONE),
+            Self::Timestamp => (
+                PhysicalType::INT64,
+                Some(LogicalType::Timestamp {
+                    is_adjusted_to_u_t_c: false,
+                    unit: TimeUnit::MICROS,
+                }),
+                ConvertedType::NONE,
+            ),
+            // Parquet has no logical type for intervals, only the legacy converted type.
+            Self::Interval => (PhysicalType::FIXED_LEN_BYTE_ARRAY, None, ConvertedType::INTERVAL),
+        };
+        let mut builder = Type::primitive_type_builder(name, physical_type)
+            .with_repetition(Repetition::OPTIONAL)
+            .with_logical_type(logical_type)
+            .with_converted_type(converted_type);
+        if self == Self::Interval {
+            builder = builder.with_length(12);
+        }
+        Ok(Arc::new(builder.build()?))
+    }
+}
+
+/// Encodes an interval in microseconds as the little-endian months, days and milliseconds of the
+/// Parquet `INTERVAL` type.
+///
+/// Returns `None` for negative intervals, which cannot be represented.
+fn encode_interval(interval: i64) -> Option<FixedLenByteArray> {
+    const MICROS_PER_DAY: i64 = 86_400_000_000;
+    let days = u32::try_from(interval / MICROS_PER_DAY).ok()?;
+    let millis = u32::try_from(interval % MICROS_PER_DAY / 1000).ok()?;
+    let mut bytes = vec![0; 4];
+    bytes.extend_from_slice(&days.to_le_bytes());
+    bytes.extend_from_slice(&millis.to_le_bytes());
+    Some(bytes.into())
+}
+
+/// Converts a value into the text stored in a string column.
+fn to_text(value: &Value) -> Option<ByteArray> {
+    let mut text = Vec::new();
+    match value {
+        Value::Bytes(b) if b.encoding() != Encoding::Binary => text.extend_from_slice(b.as_bytes()),
+        Value::Number(n) => n.write_io(&mut text, "true", "false").ok()?,
+        Value::Timestamp(ts) => write_timestamp(&mut text, "", ' ', ts).ok()?,
+        Value::Interval(i) => write_interval(&mut text, "", *i).ok()?,
+        _ => return None,
+    }
+    Some(text.into())
+}
+
+/// Writes a batch of values of a single column.
+fn write_column<T: DataType>(
+    column: &mut SerializedColumnWriter<'_>,
+    values: &[Value],
+    mut convert: impl FnMut(&Value) -> Option<T::T>,
+    expected: &'static str,
+) -> Result<(), Error> {
+    let mut def_levels = Vec::with_capacity(values.len());
+    let mut converted = Vec::with_capacity(values.len());
+    for value in values {
+        if *value == Value::Null {
+            def_levels.push(0);
+        } else if let Some(v) = convert(value) {
+            def_levels.push(1);
+            converted.push(v);
+        } else {
+            return Err(Error::UnexpectedValueType {
+                expected,
+                value: value.to_string(),
+            });
+        }
+    }
+    column.typed::<T>().write_batch(&converted, Some(&def_levels), None)?;
+    Ok(())
+}
+
+/// A [`Writer`] producing a Parquet file.
+///
+/// Every INSERT statement (i.e. every call to [`Writer::write_trailer()`]) becomes a row group.
+/// The column types are determined by the declared types, so every row group shares the same
+/// schema regardless of the values generated.
+pub struct ParquetWriter<W: Write + Send> {
+    /// The output before the first row group is written.
+    output: Option<W>,
+    /// The Parquet writer, created when the first row group is written.
+    writer: Option<SerializedFileWriter<W>>,
+    /// Properties passed to the Parquet writer.
+    properties: WriterPropertiesPtr,
+    /// Name of the Parquet schema.
+    name: String,
+    /// Column names.
+    column_names: Vec<String>,
+    /// Column types used in the Parquet schema.
+    column_types: Vec<ColumnType>,
+    /// Values of the current row group, stored column by column.
+    columns: Vec<Vec<Value>>,
+    /// Index of the next column to receive a value.
+    column_index: usize,
+}
+
+impl<W: Write + Send> std::fmt::Debug for ParquetWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParquetWriter")
+            .field("name", &self.name)
+            .field("column_names", &self.column_names)
+            .field("column_types", &self.column_types)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write + Send> ParquetWriter<W> {
+    /// Creates a new Parquet writer for the table with the given schema.
+    ///
+    /// The `compression` is applied on every page of the file.
+    pub fn new(output: W, schema: &Schema<'_>, compression: Compression) -> Self {
+        let column_names = schema
+            .column_names()
+            .map(|name| unquote_column_name(name).into_owned())
+            .collect::<Vec<_>>();
+        let column_types = schema.column_types().map(ColumnType::of_declared_type).collect();
+        let properties = WriterProperties::builder()
+            .set_compression(compression)
+            .set_created_by("dbgen".to_owned())
+            .build();
+        Self {
+            output: Some(output),
+            writer: None,
+            properties: Arc::new(properties),
+            name: unquote_column_name(schema.name).into_owned(),
+            columns: vec![Vec::new(); column_names.len()],
+            column_names,
+            column_types,
+            column_index: 0,
+        }
+    }
+
+    /// Returns the number of bytes written to the output so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.writer.as_ref().map_or(0, |w| w.bytes_written() as u64)
+    }
+
+    /// Creates the Parquet writer, if not already done.
+    fn init_writer(&mut self) -> Result<&mut SerializedFileWriter<W>, Error> {
+        if let Some(output) = self.output.take() {
+            let fields = self
+                .column_names
+                .iter()
+                .zip(&self.column_types)
+                .map(|(name, ty)| ty.to_parquet_type(name))
+                .collect::<Result<_, _>>()?;
+            let schema = Type::group_type_builder(&self.name).with_fields(fields).build()?;
+            self.writer = Some(SerializedFileWriter::new(
+                output,
+                Arc::new(schema),
+                self.properties.clone(),
+            )?);
+        }
+        Ok(self.writer.as_mut().expect("writer must be initialized"))
+    }
+
+    /// Writes all buffered rows as a row group.
+    fn write_row_group(&mut self) -> Result<(), Error> {
+        self.init_writer()?;
+        let writer = self.writer.as_mut().expect("writer must be initialized");
+        let mut row_group = writer.next_row_group()?;
+        for (values, ty) in self.columns.iter_mut().zip(&self.column_types) {
+            let mut column = row_group.next_column()?.expect("number of columns must match schema");
+            let values = mem::take(values);
+            match ty {
+                ColumnType::Boolean => write_column::<BoolType>(
+                    &mut column,
+                    &values,
+                    |v| match v {
+                        Value::Number(_) => v.is_sql_true().ok(),
+                        _ => None,
+                    },
+                    "boolean",
+                ),
+                ColumnType::Int64 => write_column::<Int64Type>(
+                    &mut column,
+                    &values,
+                    |v| match v {
+                        Value::Number(n) if n.kind() != NumberKind::Float => i64::try_from(*n).ok(),
+                        _ => None,
+                    },
+                    "64-bit signed integer",
+                ),
+                ColumnType::Double => write_column::<DoubleType>(
+                    &mut column,
+                    &values,
+                    |v| match v {
+                        Value::Number(n) => Some(f64::from(*n)),
+                        Value::Bytes(b) => std::str::from_utf8(b.as_bytes()).ok()?.trim().parse().ok(),
+                        _ => None,
+                    },
+                    "floating point number",
+                ),
+                ColumnType::String => write_column::<ByteArrayType>(&mut column, &values, to_text, "string"),
+                ColumnType::Binary => write_column::<ByteArrayType>(
+                    &mut column,
+                    &values,
+                    |v| match v {
+                        Value::Bytes(b) => Some(ByteArray::from(b.as_bytes().to_vec())),
+                        _ => None,
+                    },
+                    "byte string",
+                ),
+                ColumnType::Timestamp => write_column::<Int64Type>(
+                    &mut column,
+                    &values,
+                    |v| match v {
+                        Value::Timestamp(ts) => Some(ts.and_utc().timestamp_micros()),
+                        _ => None,
+                    },
+                    "timestamp",
+                ),
+                ColumnType::Interval => write_column::<FixedLenByteArrayType>(
+                    &mut column,
+                    &values,
+                    |v| match v {
+                        Value::Interval(i) => encode_interval(*i),
+                        _ => None,
+                    },
+                    "non-negative interval",
+                ),
+            }?;
+            column.close()?;
+        }
+        row_group.close()?;
+        Ok(())
+    }
+
+    /// Concludes the Parquet file by writing the footer, and returns the output.
+    ///
+    /// If no rows have been written, the file will contain no row groups.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file has already been finished.
+    pub fn finish(&mut self) -> Result<W, S<Error>> {
+        self.init_writer().no_span_err()?;
+        let writer = self.writer.take().expect("writer must be initialized");
+        writer.into_inner().no_span_err()
+    }
+}
+
+impl<W: Write + Send> Writer for ParquetWriter<W> {
+    fn write_value(&mut self, value: &Value) -> Result<(), S<Error>> {
+        self.columns[self.column_index].push(value.clone());
+        self.column_index += 1;
+        Ok(())
+    }
+
+    fn write_file_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.column_index = 0;
+        Ok(())
+    }
+
+    fn write_value_header(&mut self, _: &str) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_value_separator(&mut self) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_row_separator(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.column_index = 0;
+        Ok(())
+    }
+
+    fn write_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.write_row_group().no_span_err()
+    }
+
+    fn write_file_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eval::{CompileContext, State},
+        parser::Template,
+        span::Registry,
+        writer::Env,
+    };
+    use ::parquet::{
+        column::reader::get_typed_column_reader,
+        file::reader::{FileReader, SerializedFileReader},
+        record::RowAccessor,
+    };
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_parquet_writer() {
+        let mut registry = Registry::default();
+        let template = Template::parse(
+            "create table t (
+                a int {{ rownum }},
+                b text {{ 'x' || rownum }},
+                c boolean {{ rownum = 2 }},
+                d double {{ rownum / 2 }},
+                e timestamp {{ TIMESTAMP '2001-04-19 18:08:51.25' }},
+                f bytea {{ x'00ff' }},
+                g bigint {{ null }},
+                h double {{ case when rownum > 3 then rownum + 0.5 else rownum end }},
+                i text {{ case when rownum > 3 then rownum end }},
+                j interval {{ interval rownum day + interval 1500 microsecond }}
+            );",
+            &[],
+            None,
+            &mut registry,
+        )
+        .unwrap();
+        let ctx = CompileContext::new(template.variables_count);
+        let tables = template
+            .tables
+            .into_iter()
+            .map(|t| ctx.compile_table(t))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut state = State::new(1, Box::new(StepRng::new(0, 1)), ctx);
+
+        let file = tempfile::tempfile().unwrap();
+        let mut env = Env::new(&tables, &mut state, false, |table| {
+            Ok(ParquetWriter::new(
+                file.try_clone().unwrap(),
+                &table.schema(false),
+                Compression::UNCOMPRESSED,
+            ))
+        })
+        .unwrap();
+        for _ in 0..2 {
+            for _ in 0..3 {
+                env.write_row().unwrap();
+            }
+            env.write_trailer().unwrap();
+        }
+        for (_, writer) in env.tables() {
+            writer.finish().unwrap();
+        }
+
+        let reader = SerializedFileReader::new(file).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 2);
+        let schema = reader.metadata().file_metadata().schema_descr();
+        let physical_types = schema.columns().iter().map(|c| c.physical_type()).collect::<Vec<_>>();
+        assert_eq!(
+            physical_types,
+            [
+                PhysicalType::INT64,
+                PhysicalType::BYTE_ARRAY,
+                PhysicalType::BOOLEAN,
+                PhysicalType::DOUBLE,
+                PhysicalType::INT64,
+                PhysicalType::BYTE_ARRAY,
+                PhysicalType::INT64,
+                PhysicalType::DOUBLE,
+                PhysicalType::BYTE_ARRAY,
+                PhysicalType::FIXED_LEN_BYTE_ARRAY,
+            ]
+        );
+        assert_eq!(schema.column(9).converted_type(), ConvertedType::INTERVAL);
+
+        // The record API cannot read intervals, so they are read from the column chunks instead.
+        let mut intervals = Vec::new();
+        for i in 0..2 {
+            let column = reader.get_row_group(i).unwrap().get_column_reader(9).unwrap();
+            get_typed_column_reader::<FixedLenByteArrayType>(column)
+                .read_records(3, Some(&mut Vec::new()), None, &mut intervals)
+                .unwrap();
+        }
+        let fields = schema.root_schema().get_fields()[..9].to_vec();
+        let projection = Type::group_type_builder("t").with_fields(fields).build().unwrap();
+        let rows = reader
+            .get_row_iter(Some(projection))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(rows.len(), 6);
+        for ((i, row), interval) in (1_u32..).zip(&rows).zip(&intervals) {
+            assert_eq!(row.get_long(0).unwrap(), i64::from(i));
+            assert_eq!(row.get_string(1).unwrap(), &format!("x{i}"));
+            assert_eq!(row.get_bool(2).unwrap(), i == 2);
+            assert!((row.get_double(3).unwrap() - f64::from(i) / 2.0).abs() < f64::EPSILON);
+            assert_eq!(row.get_timestamp_micros(4).unwrap(), 987_703_731_250_000);
+            assert_eq!(row.get_bytes(5).unwrap().data(), b"\x00\xff");
+            row.get_long(6).unwrap_err();
+            let h = if i > 3 { f64::from(i) + 0.5 } else { f64::from(i) };
+            assert!((row.get_double(7).unwrap() - h).abs() < f64::EPSILON);
+            if i > 3 {
+                assert_eq!(row.get_string(8).unwrap(), &i.to_string());
+            } else {
+                row.get_string(8).unwrap_err();
+            }
+            let mut expected_interval = vec![0; 4];
+            expected_interval.extend_from_slice(&i.to_le_bytes());
+            expected_interval.extend_from_slice(&1_u32.to_le_bytes());
+            assert_eq!(interval.data(), expected_interval);
+        }
+    }
+}
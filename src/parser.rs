@@ -98,12 +98,12 @@ impl QName {
     ///
     /// This name is transformed from the qualified name with these changes:
     ///  - Unquoted names are all converted to lower case in the default
-    ///     collation (`XyzÄbc` → `xyzäbc`). If the lowercasing results in
-    ///     multiple characters (e.g. `İ` → `i̇`), only the first (`i`) will be
-    ///     included.
+    ///    collation (`XyzÄbc` → `xyzäbc`). If the lowercasing results in
+    ///    multiple characters (e.g. `İ` → `i̇`), only the first (`i`) will be
+    ///    included.
     ///  - Quotation marks are removed (`"Hello ""world"""` → `Hello "world"`)
     ///  - Special characters including `.`, `-` and `/` are percent-encoded,
-    ///     so the resulting string can be safely used as a filename.
+    ///    so the resulting string can be safely used as a filename.
     pub fn unique_name(&self) -> &str {
         &self.unique_name
     }
@@ -183,6 +183,12 @@ pub struct Template {
 
     /// The tables to be written out.
     pub tables: Vec<Table>,
+
+    /// The indices of the root tables with their own number of rows instead of one row per
+    /// `rownum`, and the number of rows to generate.
+    ///
+    /// These should be evaluated only once, after the global expressions.
+    pub rows_counts: Vec<(usize, S<Expr>)>,
 }
 
 /// A parsed expression.
@@ -199,18 +205,18 @@ pub enum Expr {
     /// Symbol of a local variable `@x`.
     GetVariable(usize),
     /// A variable assignment expression `@x := y`.
-    SetVariable(usize, Box<S<Expr>>),
+    SetVariable(usize, Box<S<Self>>),
     /// A function call.
     Function {
         /// The function.
         function: &'static dyn Function,
         /// Function arguments.
-        args: Vec<S<Expr>>,
+        args: Vec<S<Self>>,
     },
     /// A list of conditions.
     ///
     /// The inner array stores the condition and their corresponding results.
-    Conditions(Box<[(S<Expr>, S<Expr>)]>),
+    Conditions(Box<[(S<Self>, S<Self>)]>),
 }
 
 impl Default for Expr {
@@ -254,6 +260,7 @@ impl Template {
         let pairs = TemplateParser::parse(Rule::create_table, input).span_err(Span::default())?;
         let mut table_map = HashMap::new();
         let mut expected_child_name = None::<S<QName>>;
+        let mut expected_root_name = None::<S<QName>>;
 
         for pair in pairs {
             let span = pair.as_span();
@@ -267,7 +274,7 @@ impl Template {
                 Rule::single_table => {
                     let table = alloc.table_from_pairs(pair.into_inner())?;
                     let table_name = table.name.unique_name();
-                    if let Some(child_name) = &expected_child_name {
+                    if let Some(child_name) = expected_child_name.take() {
                         if child_name.inner.unique_name() != table_name {
                             return Err(Error::DerivedTableNameMismatch {
                                 for_each_row: child_name.inner.table_name(true).to_owned(),
@@ -276,6 +283,15 @@ impl Template {
                             .span(child_name.span));
                         }
                     }
+                    if let Some(root_name) = expected_root_name.take() {
+                        if root_name.inner.unique_name() != table_name {
+                            return Err(Error::RootTableNameMismatch {
+                                generate: root_name.inner.table_name(true).to_owned(),
+                                create_table: table.name.table_name(true).to_owned(),
+                            }
+                            .span(root_name.span));
+                        }
+                    }
                     table_map.insert(table_name.to_owned(), template.tables.len());
                     template.tables.push(table);
                 }
@@ -294,6 +310,12 @@ impl Template {
                         .span(parent.span));
                     }
                 }
+                Rule::root_directive => {
+                    // register the next table as a root table with its own number of rows.
+                    let RootDirective { table, count } = alloc.root_directive_from_pairs(pair.into_inner())?;
+                    template.rows_counts.push((template.tables.len(), count));
+                    expected_root_name = Some(table);
+                }
                 r => unreachable!("Unexpected rule {:?}", r),
             }
         }
@@ -319,6 +341,12 @@ struct DependencyDirective {
     count: S<Expr>,
 }
 
+#[derive(Default)]
+struct RootDirective {
+    table: S<QName>,
+    count: S<Expr>,
+}
+
 impl<'a> Allocator<'a> {
     fn set_schema_name(&mut self, schema: &'a str) -> Result<(), Error> {
         let pairs = TemplateParser::parse(Rule::qname, schema)?;
@@ -431,6 +459,24 @@ impl<'a> Allocator<'a> {
         Ok(res)
     }
 
+    fn root_directive_from_pairs(&mut self, pairs: Pairs<'_, Rule>) -> Result<RootDirective, S<Error>> {
+        let mut res = RootDirective::default();
+
+        for pair in pairs {
+            let span = pair.as_span();
+            match pair.as_rule() {
+                Rule::kw_rows | Rule::kw_of | Rule::kw_generate => {}
+                Rule::expr => res.count = self.expr_from_pairs(pair.into_inner())?.span(self.register(span)),
+                Rule::qname => {
+                    res.table = QName::from_pairs(pair.into_inner(), self.override_schema).span(self.register(span));
+                }
+                r => unreachable!("Unexpected rule {:?}", r),
+            }
+        }
+
+        Ok(res)
+    }
+
     /// Creates a statement expression `a; b; c`.
     fn stmt_from_pairs(&mut self, mut pairs: Pairs<'_, Rule>) -> Result<Expr, S<Error>> {
         self.expr_binary_from_pairs(pairs.next().unwrap().into_inner())
@@ -994,9 +1040,11 @@ fn test_parse_template_error() {
         "create table a (); {{ for each row of a generate 1 row of c }} create table b ();",
         "create table a (); {{ for each row of b generate 1 row of a }} create table b ();",
         "create table a (); {{ for each row of a generate (*) rows of b }} create table b ();",
+        "create table a (); {{ generate 1 row of c }} create table b ();",
+        "{{ generate 1 row of b }} create table a ();",
     ];
     for tc in &test_cases {
         let res = Template::parse(tc, &[], None, &mut registry);
-        assert!(res.is_err(), "unexpected for case {}:\n{:#?}", tc, res);
+        assert!(res.is_err(), "unexpected for case {tc}:\n{res:#?}");
     }
 }
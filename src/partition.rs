@@ -0,0 +1,142 @@
+//! Routing the rows of a table into Hive-style partition directories with `--partition-by`.
+//!
+//! Every partition directory is named `COLUMN=VALUE` after the values of the partition columns,
+//! which are removed from the data files themselves.
+
+use crate::{
+    cli::{Args, FormatName, is_same_table, parse_table_columns},
+    error::Error,
+    eval::Table,
+    format::{unquote_column_name, write_interval, write_timestamp},
+    span::{S, SpanExt as _},
+    value::Value,
+};
+use std::{borrow::Cow, fmt, io::Write as _, num::NonZeroUsize, ops::Range};
+
+/// Default maximum number of partition files kept open by every file generator thread.
+pub const DEFAULT_MAX_OPEN_PARTITIONS: NonZeroUsize = NonZeroUsize::new(64).unwrap();
+
+/// The directory name component of a NULL or empty partition value, same as Hive.
+const DEFAULT_PARTITION_NAME: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Escapes the characters which cannot appear in a partition directory name, like Hive.
+fn escape_partition_name(name: &str) -> String {
+    use fmt::Write as _;
+
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_control() || "\"#%'*/:=?\\{[]^".contains(c) {
+            write!(escaped, "%{:02X}", u32::from(c)).expect("writing into a String should not fail");
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Returns the directory name component of a partition column, i.e. `name=value`.
+pub fn partition_dir_name(name: &str, value: &Value) -> Result<String, S<Error>> {
+    let mut buf = Vec::new();
+    match value {
+        Value::Null => Ok(()),
+        Value::Number(n) => n.write_io(&mut buf, "true", "false"),
+        Value::Bytes(b) => buf.write_all(b.as_bytes()),
+        Value::Timestamp(timestamp) => write_timestamp(&mut buf, "", ' ', timestamp),
+        Value::Interval(interval) => write_interval(&mut buf, "", *interval),
+        Value::Array(_) => {
+            return Err(Error::UnexpectedValueType {
+                expected: "scalar value for partition column",
+                value: value.to_string(),
+            }
+            .no_span());
+        }
+    }
+    .expect("writing into a Vec should not fail");
+    let value = if buf.is_empty() {
+        Cow::Borrowed(DEFAULT_PARTITION_NAME)
+    } else {
+        Cow::Owned(escape_partition_name(&String::from_utf8_lossy(&buf)))
+    };
+    Ok(format!("{}={value}", escape_partition_name(name)))
+}
+
+/// How the rows of a table are routed into partition directories.
+#[derive(Debug)]
+pub struct Partitioning {
+    /// Index and (unquoted) name of every partition column.
+    pub columns: Vec<(usize, String)>,
+    /// The ranges in the table content of the column names written into the data files, i.e.
+    /// excluding the partition columns.
+    pub data_column_name_ranges: Vec<Range<usize>>,
+}
+
+/// Resolves the partition columns of every table from the `--partition-by` arguments. Arguments
+/// naming a table take precedence over those without.
+pub fn resolve_partitionings(args: &Args, tables: &[Table]) -> Result<Vec<Option<Partitioning>>, S<Error>> {
+    let specs = args
+        .partition_by
+        .iter()
+        .map(|arg| parse_table_columns(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !specs.is_empty() && args.format == FormatName::MySqlTsv {
+        return Err(
+            Error::InvalidArguments("--partition-by is not supported by the mysql-tsv format".to_owned()).no_span(),
+        );
+    }
+    for name in specs.iter().filter_map(|(name, _)| name.as_ref()) {
+        if !tables.iter().any(|table| is_same_table(name, &table.name)) {
+            return Err(Error::InvalidArguments(format!("cannot find table {}", name.table_name(true))).no_span());
+        }
+    }
+
+    tables
+        .iter()
+        .map(|table| {
+            let Some((_, columns)) = specs
+                .iter()
+                .rev()
+                .find(|(name, _)| name.as_ref().is_some_and(|name| is_same_table(name, &table.name)))
+                .or_else(|| specs.iter().rev().find(|(name, _)| name.is_none()))
+                .filter(|(_, columns)| !columns.is_empty())
+            else {
+                return Ok(None);
+            };
+            let schema = table.schema(args.qualified);
+            let column_names = schema.column_names().collect::<Vec<_>>();
+            let columns = columns
+                .iter()
+                .map(|column| {
+                    let index = column_names
+                        .iter()
+                        .position(|name| name == column || unquote_column_name(name) == column.as_str())
+                        .ok_or_else(|| {
+                            Error::InvalidArguments(format!(
+                                "cannot find column {column} in table {}",
+                                table.name.table_name(true)
+                            ))
+                            .no_span()
+                        })?;
+                    Ok((index, unquote_column_name(column_names[index]).into_owned()))
+                })
+                .collect::<Result<Vec<_>, S<Error>>>()?;
+            let data_column_name_ranges = table
+                .column_name_ranges
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| columns.iter().all(|(index, _)| index != i))
+                .map(|(_, range)| range.clone())
+                .collect::<Vec<_>>();
+            if data_column_name_ranges.is_empty() {
+                return Err(Error::InvalidArguments(format!(
+                    "cannot partition table {} by all of its columns",
+                    table.name.table_name(true)
+                ))
+                .no_span());
+            }
+            Ok(Some(Partitioning {
+                columns,
+                data_column_name_ranges,
+            }))
+        })
+        .collect()
+}
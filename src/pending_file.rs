@@ -0,0 +1,171 @@
+//! Writing the data files under a temporary path, so an interrupted run never leaves an incomplete
+//! file under the final path.
+
+use crate::{
+    cli::{Output, PathResultExt as _},
+    error::Error,
+    eval::Table,
+    span::S,
+};
+use std::{
+    ffi::OsString,
+    fs::{File, read_dir, remove_file, rename},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Returns the temporary path of a data file while it is being written, which is hidden and
+/// has a different extension so that it is not mistaken for a complete file.
+pub fn temp_file_path(path: &Path) -> PathBuf {
+    let mut file_name = OsString::from(".");
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+/// Removes the temporary data files of the tables left in the output directory by an interrupted
+/// run.
+pub fn remove_temp_files(out_dir: &Path, tables: &[Table]) -> Result<(), S<Error>> {
+    let prefixes = tables
+        .iter()
+        .map(|table| format!(".{}.", table.name.unique_name()))
+        .collect::<Vec<_>>();
+    // The partition directories of a table are nested in the directory named after it.
+    let mut dirs = vec![out_dir.to_owned()];
+    dirs.extend(tables.iter().map(|table| out_dir.join(table.name.unique_name())));
+    while let Some(dir) = dirs.pop() {
+        let entries = match read_dir(&dir) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            entries => entries.with_path("read output directory", &dir)?,
+        };
+        let is_table_dir = dir != out_dir;
+        for entry in entries {
+            let entry = entry.with_path("read output directory", &dir)?;
+            let path = entry.path();
+            let file_type = entry.file_type().with_path("read output directory", &path)?;
+            if file_type.is_dir() && is_table_dir {
+                dirs.push(path);
+                continue;
+            }
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            let is_temp_file = file_type.is_file()
+                && file_name.ends_with(".tmp")
+                && if is_table_dir {
+                    file_name.starts_with(".part-")
+                } else {
+                    prefixes.iter().any(|prefix| file_name.starts_with(prefix))
+                };
+            if is_temp_file {
+                remove_file(&path).with_path("remove temporary file", &path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A data file written under its temporary path, which is synced to the disk and renamed to its
+/// final path when it is finished after being completely written, i.e. after the compressor in
+/// front of it is finished. An unfinished file is removed when dropped.
+#[derive(Debug)]
+pub struct PendingFile {
+    /// The file at the temporary path.
+    file: File,
+    /// The temporary path.
+    temp_path: PathBuf,
+    /// The final path.
+    path: PathBuf,
+    /// The error writing into the file, if writing failed where it cannot be reported, e.g. when
+    /// the compressor is finished while being dropped.
+    error: Option<io::Error>,
+    /// Whether the file is renamed to its final path.
+    published: bool,
+}
+
+impl PendingFile {
+    /// Creates the file at the temporary path.
+    pub fn create(path: PathBuf) -> Result<Self, S<Error>> {
+        let temp_path = temp_file_path(&path);
+        let file = File::create(&temp_path).with_path("create data file", &temp_path)?;
+        Ok(Self {
+            file,
+            temp_path,
+            path,
+            error: None,
+            published: false,
+        })
+    }
+
+    /// Records the error writing into the file.
+    fn record_error<T>(&mut self, res: io::Result<T>) -> io::Result<T> {
+        if let Err(e) = &res {
+            self.error
+                .get_or_insert_with(|| io::Error::new(e.kind(), e.to_string()));
+        }
+        res
+    }
+}
+
+impl Write for PendingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let res = self.file.write(buf);
+        self.record_error(res)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        let res = self.file.flush();
+        self.record_error(res)
+    }
+}
+
+impl Output for PendingFile {
+    /// Syncs the file to the disk and renames it to the final path.
+    fn finish(&mut self) -> io::Result<()> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.file.sync_all()?;
+        rename(&self.temp_path, &self.path)?;
+        self.published = true;
+        // The rename is only durable after the directory containing the file is synced too.
+        if let Some(parent) = self.path.parent() {
+            File::open(parent)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for PendingFile {
+    fn drop(&mut self) {
+        if !self.published {
+            // The file is abandoned after an error which is already reported.
+            remove_file(&self.temp_path).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+
+    #[test]
+    fn test_pending_file() {
+        let out_dir = tempfile::tempdir().unwrap();
+        let path = out_dir.path().join("a.1.csv");
+        let temp_path = temp_file_path(&path);
+
+        let mut file = PendingFile::create(path.clone()).unwrap();
+        file.write_all(b"1\n").unwrap();
+        assert!(temp_path.exists());
+        drop(file);
+        assert!(!temp_path.exists());
+        assert!(!path.exists());
+
+        let mut file = PendingFile::create(path.clone()).unwrap();
+        file.write_all(b"1\n").unwrap();
+        file.finish().unwrap();
+        drop(file);
+        assert!(!temp_path.exists());
+        assert_eq!(read_to_string(&path).unwrap(), "1\n");
+    }
+}
@@ -0,0 +1,645 @@
+//! PostgreSQL binary `COPY` output.
+//!
+//! The binary format of `COPY` has no self-describing type information, so every value must be
+//! encoded exactly as the receive function of the column type expects. [`PgCopyBinaryWriter`]
+//! determines these types from the declared column types in the `CREATE TABLE` statement.
+
+use crate::{
+    error::Error,
+    eval::Schema,
+    format::{write_interval, write_timestamp},
+    number::{Number, NumberKind},
+    span::{ResultExt, S, SpanExt},
+    value::Value,
+    writer::Writer,
+};
+use chrono::{NaiveDate, NaiveDateTime, Timelike};
+use data_encoding::HEXLOWER_PERMISSIVE;
+use std::{convert::TryFrom, io::Write, mem, path::PathBuf};
+
+/// The signature and header at the beginning of every binary `COPY` file.
+const HEADER: &[u8] = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0";
+
+/// The PostgreSQL column type, which determines the binary encoding of a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PgType {
+    /// `boolean`.
+    Bool,
+    /// `smallint`.
+    Int2,
+    /// `integer`.
+    Int4,
+    /// `bigint`.
+    Int8,
+    /// `real`.
+    Float4,
+    /// `double precision`.
+    Float8,
+    /// `numeric`.
+    Numeric,
+    /// `bytea`.
+    Bytea,
+    /// `date`.
+    Date,
+    /// `time without time zone`.
+    Time,
+    /// `time with time zone`.
+    TimeTz,
+    /// `timestamp without time zone`.
+    Timestamp,
+    /// `timestamp with time zone`.
+    TimestampTz,
+    /// `interval`.
+    Interval,
+    /// `uuid`.
+    Uuid,
+    /// `jsonb`.
+    Jsonb,
+    /// Any type whose binary form is identical to its text form (`text`, `varchar`, `json`, enums
+    /// etc). The associated value is the type OID, which is needed for array elements.
+    Text(u32),
+    /// A one-dimensional array of the given element type.
+    Array(Box<Self>),
+}
+
+/// Splits a declared column type into words and punctuations.
+fn tokenize(ty: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+    for (i, c) in ty.char_indices() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            tokens.push(&ty[start..i]);
+        }
+        if !c.is_whitespace() {
+            tokens.push(&ty[i..(i + c.len_utf8())]);
+        }
+    }
+    if let Some(start) = word_start {
+        tokens.push(&ty[start..]);
+    }
+    tokens
+}
+
+impl PgType {
+    /// Determines the type from the declared type of a column.
+    ///
+    /// Types which are not recognized are assumed to be transferred as text.
+    fn of_declared_type(ty: &str) -> Self {
+        let ty = ty.to_ascii_lowercase();
+        let tokens = tokenize(&ty);
+        let (mut ty, mut rest) = match tokens.as_slice() {
+            ["double", "precision", rest @ ..] => (Self::Float8, rest),
+            ["character" | "char", "varying", rest @ ..] => (Self::Text(1043), rest),
+            [name, rest @ ..] => (
+                match *name {
+                    "bool" | "boolean" => Self::Bool,
+                    "smallint" | "int2" | "smallserial" | "serial2" => Self::Int2,
+                    "int" | "integer" | "int4" | "serial" | "serial4" => Self::Int4,
+                    "bigint" | "int8" | "bigserial" | "serial8" => Self::Int8,
+                    "real" | "float4" => Self::Float4,
+                    "float" | "float8" => Self::Float8,
+                    "numeric" | "decimal" => Self::Numeric,
+                    "bytea" => Self::Bytea,
+                    "date" => Self::Date,
+                    "time" => Self::Time,
+                    "timetz" => Self::TimeTz,
+                    "timestamp" => Self::Timestamp,
+                    "timestamptz" => Self::TimestampTz,
+                    "interval" => Self::Interval,
+                    "uuid" => Self::Uuid,
+                    "json" => Self::Text(114),
+                    "jsonb" => Self::Jsonb,
+                    "varchar" => Self::Text(1043),
+                    "char" | "character" | "bpchar" => Self::Text(1042),
+                    "name" => Self::Text(19),
+                    _ => Self::Text(25),
+                },
+                rest,
+            ),
+            [] => (Self::Text(25), &[][..]),
+        };
+
+        // skip the type modifiers, e.g. `numeric(10, 2)`.
+        if let ["(", tail @ ..] = rest {
+            rest = tail
+                .iter()
+                .position(|t| *t == ")")
+                .map_or(&[][..], |i| &tail[(i + 1)..]);
+        }
+        if let ["with", "time", "zone", tail @ ..] = rest {
+            ty = match ty {
+                Self::Time => Self::TimeTz,
+                Self::Timestamp => Self::TimestampTz,
+                ty => ty,
+            };
+            rest = tail;
+        } else if let ["without", "time", "zone", tail @ ..] = rest {
+            rest = tail;
+        }
+        if let ["[" | "array", ..] = rest {
+            ty = Self::Array(Box::new(ty));
+        }
+        ty
+    }
+
+    /// Returns the OID of this type.
+    fn oid(&self) -> u32 {
+        match self {
+            Self::Bool => 16,
+            Self::Bytea => 17,
+            Self::Int8 => 20,
+            Self::Int2 => 21,
+            Self::Int4 => 23,
+            Self::Float4 => 700,
+            Self::Float8 => 701,
+            Self::Date => 1082,
+            Self::Time => 1083,
+            Self::Timestamp => 1114,
+            Self::TimestampTz => 1184,
+            Self::Interval => 1186,
+            Self::TimeTz => 1266,
+            Self::Numeric => 1700,
+            Self::Uuid => 2950,
+            Self::Jsonb => 3802,
+            Self::Text(oid) => *oid,
+            Self::Array(_) => 0,
+        }
+    }
+
+    /// Returns the description of this type used in error messages.
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Bool => "PostgreSQL boolean",
+            Self::Int2 => "PostgreSQL smallint",
+            Self::Int4 => "PostgreSQL integer",
+            Self::Int8 => "PostgreSQL bigint",
+            Self::Float4 => "PostgreSQL real",
+            Self::Float8 => "PostgreSQL double precision",
+            Self::Numeric => "PostgreSQL numeric",
+            Self::Bytea => "PostgreSQL bytea",
+            Self::Date => "PostgreSQL date",
+            Self::Time => "PostgreSQL time",
+            Self::TimeTz => "PostgreSQL time with time zone",
+            Self::Timestamp => "PostgreSQL timestamp",
+            Self::TimestampTz => "PostgreSQL timestamp with time zone",
+            Self::Interval => "PostgreSQL interval",
+            Self::Uuid => "PostgreSQL uuid",
+            Self::Jsonb => "PostgreSQL jsonb",
+            Self::Text(_) => "PostgreSQL text",
+            Self::Array(_) => "PostgreSQL array",
+        }
+    }
+
+    /// Encodes a non-null value of this type into the buffer.
+    ///
+    /// Returns `None` if the value cannot be converted to this type.
+    fn encode(&self, buf: &mut Vec<u8>, value: &Value) -> Option<()> {
+        match (self, value) {
+            (Self::Bool, Value::Number(_)) => buf.push(value.is_sql_true().ok()?.into()),
+            (Self::Int2, Value::Number(n)) => buf.extend_from_slice(&i16::try_from(integer(*n)?).ok()?.to_be_bytes()),
+            (Self::Int4, Value::Number(n)) => buf.extend_from_slice(&i32::try_from(integer(*n)?).ok()?.to_be_bytes()),
+            (Self::Int8, Value::Number(n)) => buf.extend_from_slice(&integer(*n)?.to_be_bytes()),
+            // ALLOW_REASON: `real` columns are expected to lose precision.
+            #[allow(clippy::cast_possible_truncation)]
+            (Self::Float4, Value::Number(n)) => buf.extend_from_slice(&(f64::from(*n) as f32).to_be_bytes()),
+            (Self::Float8, Value::Number(n)) => buf.extend_from_slice(&f64::from(*n).to_be_bytes()),
+            (Self::Numeric, Value::Number(n)) => {
+                let mut text = Vec::new();
+                n.write_io(&mut text, "1", "0").ok()?;
+                encode_numeric(buf, &text)?;
+            }
+            (Self::Numeric, Value::Bytes(b)) => encode_numeric(buf, b.as_bytes())?,
+            (Self::Bytea | Self::Text(_), Value::Bytes(b)) => buf.extend_from_slice(b.as_bytes()),
+            (Self::Jsonb, Value::Bytes(b)) => {
+                buf.push(1);
+                buf.extend_from_slice(b.as_bytes());
+            }
+            (Self::Text(_), Value::Number(n)) => n.write_io(buf, "true", "false").ok()?,
+            (Self::Text(_), Value::Timestamp(ts)) => write_timestamp(buf, "", ' ', ts).ok()?,
+            (Self::Text(_), Value::Interval(i)) => write_interval(buf, "", *i).ok()?,
+            (Self::Date, Value::Timestamp(ts)) => {
+                let days = (ts.date() - epoch().date()).num_days();
+                buf.extend_from_slice(&i32::try_from(days).ok()?.to_be_bytes());
+            }
+            (Self::Time, Value::Timestamp(ts)) => buf.extend_from_slice(&time_of_day(ts).to_be_bytes()),
+            (Self::Time, Value::Interval(i)) => buf.extend_from_slice(&i.to_be_bytes()),
+            (Self::TimeTz, Value::Timestamp(ts)) => {
+                buf.extend_from_slice(&time_of_day(ts).to_be_bytes());
+                buf.extend_from_slice(&0_i32.to_be_bytes());
+            }
+            (Self::Timestamp | Self::TimestampTz, Value::Timestamp(ts)) => {
+                let micros = (*ts - epoch()).num_microseconds()?;
+                buf.extend_from_slice(&micros.to_be_bytes());
+            }
+            (Self::Interval, Value::Interval(i)) => {
+                buf.extend_from_slice(&i.to_be_bytes());
+                buf.extend_from_slice(&[0; 8]);
+            }
+            (Self::Uuid, Value::Bytes(b)) if b.len() == 16 => buf.extend_from_slice(b.as_bytes()),
+            (Self::Uuid, Value::Bytes(b)) => {
+                let hex = b
+                    .as_bytes()
+                    .iter()
+                    .copied()
+                    .filter(|c| !matches!(c, b'-' | b'{' | b'}'))
+                    .collect::<Vec<_>>();
+                let uuid = HEXLOWER_PERMISSIVE.decode(&hex).ok()?;
+                if uuid.len() != 16 {
+                    return None;
+                }
+                buf.extend_from_slice(&uuid);
+            }
+            (Self::Array(element_type), Value::Array(array)) => {
+                let has_null = array.iter().any(|v| v == Value::Null);
+                let len = i32::try_from(array.len()).ok()?;
+                buf.extend_from_slice(&1_i32.to_be_bytes());
+                buf.extend_from_slice(&i32::from(has_null).to_be_bytes());
+                buf.extend_from_slice(&element_type.oid().to_be_bytes());
+                buf.extend_from_slice(&len.to_be_bytes());
+                buf.extend_from_slice(&1_i32.to_be_bytes());
+                for element in array.iter() {
+                    if let Value::Array(_) = element {
+                        return None;
+                    }
+                    encode_field(buf, element_type, &element)?;
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+/// Returns the epoch of PostgreSQL timestamps, 2000-01-01 00:00:00.
+fn epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2000, 1, 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .expect("valid date")
+}
+
+/// Returns the number of microseconds since midnight.
+fn time_of_day(ts: &NaiveDateTime) -> i64 {
+    i64::from(ts.num_seconds_from_midnight()) * 1_000_000 + i64::from(ts.nanosecond() / 1000)
+}
+
+/// Converts a number into an integer, rejecting floating point numbers.
+fn integer(n: Number) -> Option<i64> {
+    if n.kind() == NumberKind::Float {
+        None
+    } else {
+        i64::try_from(n).ok()
+    }
+}
+
+/// Encodes a length-prefixed field, or -1 for NULL.
+fn encode_field(buf: &mut Vec<u8>, ty: &PgType, value: &Value) -> Option<()> {
+    if *value == Value::Null {
+        buf.extend_from_slice(&(-1_i32).to_be_bytes());
+        return Some(());
+    }
+    let start = buf.len();
+    buf.extend_from_slice(&[0; 4]);
+    ty.encode(buf, value)?;
+    let len = i32::try_from(buf.len() - start - 4).ok()?;
+    buf[start..(start + 4)].copy_from_slice(&len.to_be_bytes());
+    Some(())
+}
+
+/// Encodes a decimal number like `-12.5e3` in the binary format of `numeric`.
+///
+/// The number is stored as base-10000 digits, together with the position of the decimal point
+/// (`weight`) and the number of decimal digits after the decimal point (`dscale`).
+fn encode_numeric(buf: &mut Vec<u8>, text: &[u8]) -> Option<()> {
+    const NUMERIC_NEG: u16 = 0x4000;
+    const NUMERIC_NAN: u16 = 0xc000;
+
+    let text = std::str::from_utf8(text).ok()?.trim();
+    if text.eq_ignore_ascii_case("nan") {
+        buf.extend_from_slice(&[0, 0, 0, 0]);
+        buf.extend_from_slice(&NUMERIC_NAN.to_be_bytes());
+        buf.extend_from_slice(&[0, 0]);
+        return Some(());
+    }
+
+    let (negative, text) = match text.as_bytes().first()? {
+        b'-' => (true, &text[1..]),
+        b'+' => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(i) => (&text[..i], text[(i + 1)..].parse::<i64>().ok()?),
+        None => (text, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    let mut digits = Vec::with_capacity(int_part.len() + frac_part.len());
+    for c in int_part.bytes().chain(frac_part.bytes()) {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        digits.push(c - b'0');
+    }
+
+    // the number is `digits × 10^(-scale)`.
+    let mut scale = i64::try_from(frac_part.len()).ok()? - exponent;
+    if scale < 0 {
+        digits.resize(digits.len() + usize::try_from(-scale).ok()?, 0);
+        scale = 0;
+    }
+    let dscale = u16::try_from(scale).ok().filter(|s| *s <= 0x3fff)?;
+    let scale = usize::from(dscale);
+    if digits.len() < scale {
+        digits.splice(0..0, std::iter::repeat_n(0, scale - digits.len()));
+    }
+
+    // align the digits into groups of 4 around the decimal point.
+    let int_len = digits.len() - scale;
+    let leading_zeros = (4 - int_len % 4) % 4;
+    let trailing_zeros = (4 - scale % 4) % 4;
+    digits.splice(0..0, std::iter::repeat_n(0, leading_zeros));
+    digits.resize(digits.len() + trailing_zeros, 0);
+    let mut groups = digits
+        .chunks(4)
+        .map(|c| c.iter().fold(0_i16, |acc, d| acc * 10 + i16::from(*d)))
+        .collect::<Vec<_>>();
+    let mut weight = i64::try_from((int_len + leading_zeros) / 4).ok()? - 1;
+
+    let first_nonzero = groups.iter().position(|g| *g != 0).unwrap_or(groups.len());
+    groups.drain(..first_nonzero);
+    weight -= i64::try_from(first_nonzero).ok()?;
+    let last_nonzero = groups.iter().rposition(|g| *g != 0).map_or(0, |i| i + 1);
+    groups.truncate(last_nonzero);
+    let sign = if groups.is_empty() {
+        weight = 0;
+        0
+    } else if negative {
+        NUMERIC_NEG
+    } else {
+        0
+    };
+
+    buf.extend_from_slice(&i16::try_from(groups.len()).ok()?.to_be_bytes());
+    buf.extend_from_slice(&i16::try_from(weight).ok()?.to_be_bytes());
+    buf.extend_from_slice(&sign.to_be_bytes());
+    buf.extend_from_slice(&dscale.to_be_bytes());
+    for group in groups {
+        buf.extend_from_slice(&group.to_be_bytes());
+    }
+    Some(())
+}
+
+/// A [`Writer`] producing a file in the binary format of PostgreSQL `COPY`.
+///
+/// Each value is encoded according to the declared type of its column. Types not recognized are
+/// transferred in their text form, which is valid for textual types, domains and enums.
+#[derive(Debug)]
+pub struct PgCopyBinaryWriter<W: Write> {
+    /// The output.
+    output: W,
+    /// Path of the output, for error reporting.
+    path: PathBuf,
+    /// Column types.
+    column_types: Vec<PgType>,
+    /// Index of the next column to receive a value.
+    column_index: usize,
+    /// Buffer of the encoded row.
+    buffer: Vec<u8>,
+    /// Number of bytes written to the output so far.
+    bytes_written: u64,
+}
+
+impl<W: Write> PgCopyBinaryWriter<W> {
+    /// Creates a new binary `COPY` writer for the table with the given schema.
+    ///
+    /// The `path` is only used for reporting I/O errors.
+    pub fn new(output: W, path: PathBuf, schema: &Schema<'_>) -> Self {
+        Self {
+            output,
+            path,
+            column_types: schema.column_types().map(PgType::of_declared_type).collect(),
+            column_index: 0,
+            buffer: Vec::new(),
+            bytes_written: 0,
+        }
+    }
+
+    /// Returns the number of bytes written to the output so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Writes the bytes into the output.
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), S<Error>> {
+        self.output.write_all(bytes).map_err(|source| {
+            Error::Io {
+                action: "write binary COPY data",
+                path: self.path.clone(),
+                source,
+            }
+            .no_span()
+        })?;
+        self.bytes_written += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Writes out the row encoded so far, and starts a new row.
+    fn flush_row(&mut self) -> Result<(), S<Error>> {
+        if !self.buffer.is_empty() {
+            let mut buffer = mem::take(&mut self.buffer);
+            self.write_all(&buffer)?;
+            buffer.clear();
+            self.buffer = buffer;
+        }
+        Ok(())
+    }
+
+    /// Starts encoding a new row.
+    fn start_row(&mut self) -> Result<(), S<Error>> {
+        self.flush_row()?;
+        let fields_count = i16::try_from(self.column_types.len()).expect("too many columns");
+        self.buffer.extend_from_slice(&fields_count.to_be_bytes());
+        self.column_index = 0;
+        Ok(())
+    }
+
+    /// Concludes the file by writing the trailer.
+    pub fn finish(&mut self) -> Result<(), S<Error>> {
+        self.flush_row()?;
+        self.write_all(&(-1_i16).to_be_bytes())
+    }
+
+    /// Returns the output.
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
+
+impl<W: Write> Writer for PgCopyBinaryWriter<W> {
+    fn write_value(&mut self, value: &Value) -> Result<(), S<Error>> {
+        let ty = &self.column_types[self.column_index];
+        encode_field(&mut self.buffer, ty, value)
+            .ok_or_else(|| Error::UnexpectedValueType {
+                expected: ty.description(),
+                value: value.to_string(),
+            })
+            .no_span_err()?;
+        self.column_index += 1;
+        Ok(())
+    }
+
+    fn write_file_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.write_all(HEADER)
+    }
+
+    fn write_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.start_row()
+    }
+
+    fn write_value_header(&mut self, _: &str) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_value_separator(&mut self) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_row_separator(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.start_row()
+    }
+
+    fn write_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.flush_row()
+    }
+
+    fn write_file_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eval::{CompileContext, State},
+        parser::Template,
+        span::Registry,
+        writer::Env,
+    };
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_of_declared_type() {
+        let test_cases = [
+            ("int not null", PgType::Int4),
+            ("BIGINT PRIMARY KEY", PgType::Int8),
+            ("double precision", PgType::Float8),
+            ("numeric(10, 2) default 0", PgType::Numeric),
+            ("character varying(32)", PgType::Text(1043)),
+            ("char(4)", PgType::Text(1042)),
+            ("timestamp(3) with time zone", PgType::TimestampTz),
+            ("timestamp without time zone", PgType::Timestamp),
+            ("time with time zone", PgType::TimeTz),
+            ("int[]", PgType::Array(Box::new(PgType::Int4))),
+            ("varchar(10) array", PgType::Array(Box::new(PgType::Text(1043)))),
+            ("my_enum", PgType::Text(25)),
+            ("", PgType::Text(25)),
+        ];
+        for (ty, expected) in test_cases {
+            assert_eq!(PgType::of_declared_type(ty), expected, "{ty}");
+        }
+    }
+
+    #[test]
+    fn test_encode_numeric() {
+        let test_cases: [(&[u8], &[i16]); 8] = [
+            (b"0", &[0, 0, 0, 0]),
+            (b"123.45", &[2, 0, 0, 2, 123, 4500]),
+            (b"-12345678.9", &[3, 1, 0x4000, 1, 1234, 5678, 9000]),
+            (b"1e20", &[1, 5, 0, 0, 1]),
+            (b"0.001", &[1, -1, 0, 3, 10]),
+            (b"1.5e-7", &[1, -2, 0, 8, 15]),
+            (b"-0.00", &[0, 0, 0, 2]),
+            (b"NaN", &[0, 0, -0x4000, 0]),
+        ];
+        for (text, expected) in test_cases {
+            let mut actual = Vec::new();
+            encode_numeric(&mut actual, text).unwrap();
+            let expected = expected.iter().flat_map(|v| v.to_be_bytes()).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{}", String::from_utf8_lossy(text));
+        }
+        assert_eq!(encode_numeric(&mut Vec::new(), b"1.2.3"), None);
+        assert_eq!(encode_numeric(&mut Vec::new(), b"abc"), None);
+    }
+
+    #[test]
+    fn test_pg_copy_binary_writer() {
+        let mut registry = Registry::default();
+        let template = Template::parse(
+            "create table t (
+                a smallint {{ rownum }},
+                b text {{ 'x' }},
+                c boolean {{ rownum = 2 }},
+                d numeric(4, 1) {{ 2.5 }},
+                e timestamp {{ TIMESTAMP '2000-01-02 00:00:00.5' }},
+                f interval {{ INTERVAL 3 SECOND }},
+                g bytea {{ null }},
+                h int[] {{ array[1, null] }}
+            );",
+            &[],
+            None,
+            &mut registry,
+        )
+        .unwrap();
+        let ctx = CompileContext::new(template.variables_count);
+        let tables = template
+            .tables
+            .into_iter()
+            .map(|t| ctx.compile_table(t))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut state = State::new(1, Box::new(StepRng::new(0, 1)), ctx);
+
+        let mut output = Vec::new();
+        let mut output_ref = Some(&mut output);
+        let mut env = Env::new(&tables, &mut state, false, |table| {
+            Ok(PgCopyBinaryWriter::new(
+                output_ref.take().unwrap(),
+                PathBuf::new(),
+                &table.schema(false),
+            ))
+        })
+        .unwrap();
+        for _ in 0..2 {
+            env.write_row().unwrap();
+        }
+        env.write_trailer().unwrap();
+        for (_, writer) in env.tables() {
+            writer.finish().unwrap();
+        }
+        drop(env);
+
+        let mut expected = HEADER.to_vec();
+        for (a, c) in [(1, 0), (2, 1)] {
+            expected.extend_from_slice(b"\0\x08");
+            expected.extend_from_slice(b"\0\0\0\x02\0");
+            expected.push(a);
+            expected.extend_from_slice(b"\0\0\0\x01x");
+            expected.extend_from_slice(b"\0\0\0\x01");
+            expected.push(c);
+            expected.extend_from_slice(b"\0\0\0\x0c\0\x02\0\0\0\0\0\x01\0\x02\x13\x88");
+            expected.extend_from_slice(b"\0\0\0\x08\0\0\0\x14\x1d\xdf\x01\x20");
+            expected.extend_from_slice(b"\0\0\0\x10\0\0\0\0\0\x2d\xc6\xc0\0\0\0\0\0\0\0\0");
+            expected.extend_from_slice(b"\xff\xff\xff\xff");
+            expected.extend_from_slice(b"\0\0\0\x20\0\0\0\x01\0\0\0\x01\0\0\0\x17\0\0\0\x02\0\0\0\x01");
+            expected.extend_from_slice(b"\0\0\0\x04\0\0\0\x01\xff\xff\xff\xff");
+        }
+        expected.extend_from_slice(b"\xff\xff");
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,264 @@
+//! Loading rows directly into a PostgreSQL database.
+//!
+//! Instead of producing data files, the rows encoded in the `pg-copy` text format are streamed
+//! into the server through `COPY ... FROM STDIN`. Every generator thread uses its own connection,
+//! and every batch of rows becomes one `COPY` command, which is retried as a whole when it fails
+//! with a transient error.
+
+use crate::error::Error;
+use postgres::{
+    Client, Config, NoTls,
+    error::{DbError, SqlState},
+};
+use std::{
+    fmt,
+    io::{self, Write},
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread::sleep,
+    time::Duration,
+};
+
+/// Checks whether a failed `COPY` is transient, i.e. executing the same `COPY` again may succeed.
+///
+/// Errors which do not come from the PostgreSQL client are never transient.
+fn is_transient(e: &io::Error) -> bool {
+    e.get_ref()
+        .and_then(|e| e.downcast_ref::<postgres::Error>())
+        .is_some_and(|e| is_transient_state(e.as_db_error().map(DbError::code)))
+}
+
+/// Checks whether an error with the given SQLSTATE is transient. Errors without a SQLSTATE come
+/// from the connection instead of the server, and are always transient.
+fn is_transient_state(code: Option<&SqlState>) -> bool {
+    code.is_none_or(|code| {
+        [
+            SqlState::T_R_SERIALIZATION_FAILURE,
+            SqlState::T_R_DEADLOCK_DETECTED,
+            SqlState::TOO_MANY_CONNECTIONS,
+            SqlState::ADMIN_SHUTDOWN,
+            SqlState::CRASH_SHUTDOWN,
+            SqlState::CANNOT_CONNECT_NOW,
+        ]
+        .contains(code)
+    })
+}
+
+/// Constructs the `COPY ... FROM STDIN` statement of a table, with rows encoded in the `pg-copy`
+/// format using `null_string` for NULL.
+fn copy_statement(name: &str, null_string: &str) -> String {
+    let null_literal = null_string.replace('\'', "''");
+    format!("COPY {name} FROM STDIN WITH (NULL '{null_literal}')")
+}
+
+/// A table receiving rows through `COPY`.
+#[derive(Debug)]
+struct CopyTarget {
+    /// The table name.
+    name: String,
+    /// The `COPY ... FROM STDIN` statement.
+    statement: String,
+    /// Number of rows copied into the table so far.
+    rows_count: AtomicU64,
+}
+
+/// A PostgreSQL database shared by all writers.
+pub struct PostgresDatabase {
+    /// The parsed connection URL.
+    config: Config,
+    /// SQL statements executed on every new connection.
+    session_init: Option<String>,
+    /// Maximum number of times a batch failing with a transient error is retried.
+    max_retries: u32,
+    /// The connection of each generator thread, opened on first use.
+    connections: Vec<Mutex<Option<Client>>>,
+    /// The tables receiving the rows.
+    targets: Vec<CopyTarget>,
+}
+
+impl fmt::Debug for PostgresDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PostgresDatabase")
+            .field("config", &self.config)
+            .field("session_init", &self.session_init)
+            .field("max_retries", &self.max_retries)
+            .field("targets", &self.targets)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PostgresDatabase {
+    /// Connects to the database at the given URL.
+    ///
+    /// `threads_count` is the number of generator threads, and `tables` are the names of all
+    /// tables. The `null_string` must be the same as the one used to encode the rows.
+    pub fn connect<'a>(
+        url: &str,
+        threads_count: usize,
+        tables: impl IntoIterator<Item = &'a str>,
+        null_string: &str,
+        session_init: Option<String>,
+        max_retries: u32,
+    ) -> Result<Self, Error> {
+        let mut database = Self {
+            config: url.parse()?,
+            session_init,
+            max_retries,
+            connections: (0..threads_count.max(1)).map(|_| Mutex::new(None)).collect(),
+            targets: tables
+                .into_iter()
+                .map(|name| CopyTarget {
+                    name: name.to_owned(),
+                    statement: copy_statement(name, null_string),
+                    rows_count: AtomicU64::new(0),
+                })
+                .collect(),
+        };
+        // Connect once here, so an unreachable server is reported before generating anything.
+        let client = database.open_connection()?;
+        *database.connections[0]
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner) = Some(client);
+        Ok(database)
+    }
+
+    /// Opens a new connection and initializes the session.
+    fn open_connection(&self) -> Result<Client, postgres::Error> {
+        let mut client = self.config.connect(NoTls)?;
+        if let Some(session_init) = &self.session_init {
+            client.batch_execute(session_init)?;
+        }
+        Ok(client)
+    }
+
+    /// Locks the connection of the current generator thread, opening it if necessary.
+    fn lock_connection(&self) -> Result<MutexGuard<'_, Option<Client>>, postgres::Error> {
+        let index = rayon::current_thread_index().unwrap_or(0) % self.connections.len();
+        let mut connection = self.connections[index].lock().unwrap_or_else(PoisonError::into_inner);
+        if connection.is_none() {
+            *connection = Some(self.open_connection()?);
+        }
+        Ok(connection)
+    }
+
+    /// Executes SQL statements separated by semicolons, e.g. `CREATE TABLE` statements.
+    pub fn execute_batch(&self, sql: &str) -> Result<(), Error> {
+        let mut connection = self.lock_connection()?;
+        let client = connection.as_mut().expect("connection should be opened");
+        client.batch_execute(sql)?;
+        Ok(())
+    }
+
+    /// Copies rows encoded in the `pg-copy` format into a table. Returns the number of rows copied.
+    ///
+    /// The errors of the client are wrapped in `io::Error`, like those reported by the `COPY` writer.
+    fn try_copy_in(&self, target: &CopyTarget, data: &[u8]) -> io::Result<u64> {
+        let mut connection = self.lock_connection().map_err(io::Error::other)?;
+        let client = connection.as_mut().expect("connection should be opened");
+        let result = (|| {
+            let mut writer = client.copy_in(&target.statement).map_err(io::Error::other)?;
+            // Returning early drops the writer without finishing, which aborts the `COPY`, so a
+            // failed batch is never partially committed.
+            writer.write_all(data)?;
+            writer.finish().map_err(io::Error::other)
+        })();
+        if client.is_closed() {
+            // The connection is broken, so reconnect on the next attempt.
+            *connection = None;
+        }
+        result
+    }
+
+    /// Copies rows into a table, retrying on transient errors.
+    fn copy_in(&self, table_index: usize, data: &[u8]) -> io::Result<()> {
+        let target = &self.targets[table_index];
+        let mut retries = 0;
+        loop {
+            match self.try_copy_in(target, data) {
+                Ok(rows_count) => {
+                    target.rows_count.fetch_add(rows_count, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(e) if retries < self.max_retries && is_transient(&e) => {
+                    sleep(Duration::from_millis(100 << retries.min(6)));
+                    retries += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the name and number of rows copied of every table.
+    pub fn rows_counts(&self) -> impl Iterator<Item = (&str, u64)> + '_ {
+        self.targets
+            .iter()
+            .map(|target| (&*target.name, target.rows_count.load(Ordering::Relaxed)))
+    }
+}
+
+/// An output buffering rows of a table, which are copied into the database when flushed.
+#[derive(Debug)]
+pub struct PostgresCopyWriter {
+    /// The target database.
+    database: Arc<PostgresDatabase>,
+    /// Index of the table in the database targets.
+    table_index: usize,
+    /// The rows not yet copied.
+    buffer: Vec<u8>,
+}
+
+impl PostgresCopyWriter {
+    /// Creates a new writer copying into the table at the given index.
+    pub fn new(database: Arc<PostgresDatabase>, table_index: usize) -> Self {
+        Self {
+            database,
+            table_index,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl Write for PostgresCopyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.database.copy_in(self.table_index, &self.buffer)?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        assert!(is_transient_state(None));
+        assert!(is_transient_state(Some(&SqlState::T_R_DEADLOCK_DETECTED)));
+        assert!(is_transient_state(Some(&SqlState::ADMIN_SHUTDOWN)));
+        assert!(!is_transient_state(Some(&SqlState::UNIQUE_VIOLATION)));
+        assert!(!is_transient_state(Some(&SqlState::UNDEFINED_TABLE)));
+
+        // errors not coming from the client, e.g. a broken output, cannot be retried.
+        assert!(!is_transient(&io::Error::other("failed to encode row")));
+        assert!(!is_transient(&io::Error::from(io::ErrorKind::BrokenPipe)));
+    }
+
+    #[test]
+    fn test_copy_statement() {
+        assert_eq!(
+            copy_statement("\"s\".\"t\"", r"\N"),
+            r#"COPY "s"."t" FROM STDIN WITH (NULL '\N')"#
+        );
+        assert_eq!(copy_statement("t", "it's"), "COPY t FROM STDIN WITH (NULL 'it''s')");
+        assert_eq!(copy_statement("t", ""), "COPY t FROM STDIN WITH (NULL '')");
+    }
+}
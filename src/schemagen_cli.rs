@@ -7,8 +7,11 @@
     clippy::cast_sign_loss
 )]
 
-use crate::{error::Error, parser::QName};
-use clap::{Parser, ValueEnum};
+use crate::{format::Dialect, parser::QName};
+use clap::{
+    Parser,
+    builder::{PossibleValuesParser, TypedValueParser as _},
+};
 use rand::{Rng, RngCore, rngs::OsRng, seq::SliceRandom};
 use rand_distr::{Distribution, Geometric, LogNormal, Pareto, weighted_alias::WeightedAliasIndex};
 use std::{
@@ -17,7 +20,6 @@ use std::{
     fmt::Write,
     iter::{once, repeat_with},
     mem::replace,
-    str::FromStr,
 };
 
 /// Arguments to the `dbschemagen` CLI program.
@@ -37,7 +39,11 @@ pub struct Args {
     pub tables_count: u32,
 
     /// SQL dialect.
-    #[arg(short, long, value_enum)]
+    #[arg(
+        short,
+        long,
+        value_parser = PossibleValuesParser::new(["mysql", "postgresql", "sqlite"]).try_map(|s| s.parse::<Dialect>()),
+    )]
     pub dialect: Dialect,
 
     /// Number of INSERT statements per file.
@@ -57,35 +63,6 @@ pub struct Args {
     pub args: Vec<String>,
 }
 
-/// The SQL dialect used when generating the schemas.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
-#[value(rename_all = "lowercase")]
-pub enum Dialect {
-    /// MySQL dialect.
-    MySQL,
-    /// PostgreSQL dialect.
-    PostgreSQL,
-    /// SQLite dialect.
-    SQLite,
-}
-
-impl FromStr for Dialect {
-    type Err = Error;
-    fn from_str(dialect: &str) -> Result<Self, Self::Err> {
-        Ok(match dialect {
-            "mysql" => Self::MySQL,
-            "postgresql" => Self::PostgreSQL,
-            "sqlite" => Self::SQLite,
-            _ => {
-                return Err(Error::UnsupportedCliParameter {
-                    kind: "SQL dialect",
-                    value: dialect.to_owned(),
-                });
-            }
-        })
-    }
-}
-
 struct Column {
     /// The column type.
     ty: String,
@@ -99,6 +76,9 @@ struct Column {
     nullable: bool,
 }
 
+/// The panic message of the dialects rejected when parsing the arguments.
+const UNSUPPORTED_DIALECT: &str = "dbschemagen only generates MySQL, PostgreSQL and SQLite schemas";
+
 type ColumnGenerator = fn(Dialect, &mut dyn RngCore) -> Column;
 
 fn gen_int_column(dialect: Dialect, rng: &mut dyn RngCore) -> Column {
@@ -126,6 +106,7 @@ fn gen_int_column(dialect: Dialect, rng: &mut dyn RngCore) -> Column {
         (Dialect::PostgreSQL, true, 3..=6) => "bigint",
         (Dialect::PostgreSQL, true, _) => "numeric(20)",
         (Dialect::SQLite, _, _) => "integer",
+        (Dialect::SQLServer | Dialect::Oracle, _, _) => unreachable!("{UNSUPPORTED_DIALECT}"),
     };
     let ty = format!("{ty} not null");
     let (min, max) = if unsigned {
@@ -157,6 +138,7 @@ fn gen_serial_column(dialect: Dialect, _: &mut dyn RngCore) -> Column {
         Dialect::MySQL => "bigint unsigned not null",
         Dialect::PostgreSQL => "bigserial",
         Dialect::SQLite => "integer not null",
+        Dialect::SQLServer | Dialect::Oracle => unreachable!("{UNSUPPORTED_DIALECT}"),
     };
     Column {
         ty: ty.to_owned(),
@@ -212,6 +194,7 @@ fn gen_timestamp_column(dialect: Dialect, _: &mut dyn RngCore) -> Column {
     let ty = match dialect {
         Dialect::SQLite => "text not null",
         Dialect::MySQL | Dialect::PostgreSQL => "timestamp not null",
+        Dialect::SQLServer | Dialect::Oracle => unreachable!("{UNSUPPORTED_DIALECT}"),
     };
     Column {
         ty: ty.to_owned(),
@@ -229,6 +212,7 @@ fn gen_datetime_column(dialect: Dialect, _: &mut dyn RngCore) -> Column {
         Dialect::SQLite => "text not null",
         Dialect::MySQL => "datetime not null",
         Dialect::PostgreSQL => "timestamp not null",
+        Dialect::SQLServer | Dialect::Oracle => unreachable!("{UNSUPPORTED_DIALECT}"),
     };
     Column {
         ty: ty.to_owned(),
@@ -0,0 +1,304 @@
+//! SQLite database output.
+//!
+//! Instead of producing data files, [`SqliteWriter`] inserts the generated rows into a SQLite
+//! database using a prepared statement. SQLite only allows a single writer at a time, so all
+//! writers share one connection through [`SqliteDatabase`], and every batch of rows is inserted in
+//! its own transaction while holding the lock.
+
+use crate::{
+    error::Error,
+    eval::Schema,
+    format::{Format, Options, SqlFormat, write_interval, write_timestamp},
+    number::NumberKind,
+    span::{ResultExt, S, SpanExt},
+    value::Value,
+    writer::Writer,
+};
+use rand_regex::Encoding;
+use rusqlite::{Connection, params_from_iter, types::Value as SqliteValue};
+use std::{
+    num::NonZeroU64,
+    path::Path,
+    sync::{Mutex, PoisonError},
+};
+
+/// A SQLite database shared by all writers.
+#[derive(Debug)]
+pub struct SqliteDatabase {
+    /// The connection to the database.
+    connection: Mutex<Connection>,
+}
+
+impl SqliteDatabase {
+    /// Opens the database file, creating it if it does not exist.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            connection: Mutex::new(Connection::open(path)?),
+        })
+    }
+
+    /// Executes SQL statements separated by semicolons, e.g. `CREATE TABLE` statements.
+    pub fn execute_batch(&self, sql: &str) -> Result<(), Error> {
+        let connection = self.connection.lock().unwrap_or_else(PoisonError::into_inner);
+        connection.execute_batch(sql)?;
+        Ok(())
+    }
+
+    /// Executes the prepared statement once for every row in a single transaction.
+    fn insert_rows(&self, sql: &str, values: &[SqliteValue], columns_count: usize) -> Result<(), Error> {
+        let mut connection = self.connection.lock().unwrap_or_else(PoisonError::into_inner);
+        let transaction = connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(sql)?;
+            for row in values.chunks(columns_count) {
+                statement.execute(params_from_iter(row))?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+/// Converts a value into a SQLite value.
+///
+/// Booleans become integers, timestamps and intervals are stored as text in the same form as the
+/// SQL format, and binary strings are stored as blobs. Returns `None` for arrays and integers
+/// beyond the 64-bit signed range, which SQLite does not support.
+fn to_sqlite_value(value: &Value) -> Option<SqliteValue> {
+    Some(match value {
+        Value::Null => SqliteValue::Null,
+        Value::Number(n) => match n.kind() {
+            NumberKind::Bool | NumberKind::Integer => SqliteValue::Integer(i64::try_from(*n).ok()?),
+            NumberKind::Float => SqliteValue::Real(f64::from(*n)),
+        },
+        Value::Bytes(b) if b.encoding() == Encoding::Binary => SqliteValue::Blob(b.as_bytes().to_vec()),
+        Value::Bytes(b) => SqliteValue::Text(String::from_utf8_lossy(b.as_bytes()).into_owned()),
+        Value::Timestamp(timestamp) => {
+            let mut buf = Vec::new();
+            write_timestamp(&mut buf, "", ' ', timestamp).ok()?;
+            SqliteValue::Text(String::from_utf8(buf).ok()?)
+        }
+        Value::Interval(interval) => {
+            let mut buf = Vec::new();
+            write_interval(&mut buf, "", *interval).ok()?;
+            SqliteValue::Text(String::from_utf8(buf).ok()?)
+        }
+        Value::Array(_) => return None,
+    })
+}
+
+/// A [`Writer`] inserting rows into a table of a SQLite database.
+///
+/// The rows of every `transaction_size` INSERT statements (i.e. calls to
+/// [`Writer::write_trailer()`]) are buffered and then inserted in a single transaction.
+#[derive(Debug)]
+pub struct SqliteWriter<'a> {
+    /// The target database.
+    database: &'a SqliteDatabase,
+    /// The prepared INSERT statement inserting a single row.
+    sql: String,
+    /// Number of columns of the table.
+    columns_count: usize,
+    /// Values of the buffered rows.
+    values: Vec<SqliteValue>,
+    /// Number of INSERT statements in every transaction.
+    transaction_size: u64,
+    /// Number of INSERT statements buffered.
+    statements_count: u64,
+}
+
+impl<'a> SqliteWriter<'a> {
+    /// Creates a new SQLite writer for the table with the given schema.
+    ///
+    /// The INSERT statement is constructed from the SQL format with the given options, so the
+    /// insert mode, conflict clauses and column names are honored.
+    pub fn new(database: &'a SqliteDatabase, schema: &Schema<'_>, options: &Options) -> Self {
+        let format = SqlFormat(options);
+        let columns_count = schema.column_names().count();
+        let mut sql = Vec::new();
+        let write_sql = |sql: &mut Vec<u8>| {
+            format.write_header(sql, schema)?;
+            for i in 0..columns_count {
+                if i != 0 {
+                    format.write_value_separator(sql)?;
+                }
+                sql.push(b'?');
+            }
+            format.write_trailer(sql, schema)
+        };
+        write_sql(&mut sql).expect("writing into a Vec should not fail");
+        Self {
+            database,
+            sql: String::from_utf8_lossy(&sql).into_owned(),
+            columns_count,
+            values: Vec::new(),
+            transaction_size: options.transaction_size.map_or(1, NonZeroU64::get),
+            statements_count: 0,
+        }
+    }
+
+    /// Inserts all buffered rows into the database.
+    fn flush(&mut self) -> Result<(), Error> {
+        self.statements_count = 0;
+        if self.values.is_empty() {
+            return Ok(());
+        }
+        self.database.insert_rows(&self.sql, &self.values, self.columns_count)?;
+        self.values.clear();
+        Ok(())
+    }
+}
+
+impl Writer for SqliteWriter<'_> {
+    fn write_value(&mut self, value: &Value) -> Result<(), S<Error>> {
+        let converted = to_sqlite_value(value).ok_or_else(|| {
+            Error::UnexpectedValueType {
+                expected: "scalar value for SQLite output (integers must fit in 64 bits)",
+                value: value.to_string(),
+            }
+            .no_span()
+        })?;
+        self.values.push(converted);
+        Ok(())
+    }
+
+    fn write_file_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_header(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_value_header(&mut self, _: &str) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_value_separator(&mut self) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_row_separator(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        Ok(())
+    }
+
+    fn write_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.statements_count += 1;
+        if self.statements_count >= self.transaction_size {
+            self.flush().no_span_err()?;
+        }
+        Ok(())
+    }
+
+    fn write_file_trailer(&mut self, _: &Schema<'_>) -> Result<(), S<Error>> {
+        self.flush().no_span_err()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eval::{CompileContext, State},
+        format::Dialect,
+        parser::Template,
+        span::Registry,
+        writer::Env,
+    };
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_sqlite_writer() {
+        let mut registry = Registry::default();
+        let template = Template::parse(
+            "create table t (
+                a int {{ rownum }},
+                b text {{ 'x' || rownum }},
+                c boolean {{ rownum = 2 }},
+                d double {{ rownum / 2 }},
+                e timestamp {{ TIMESTAMP '2001-04-19 18:08:51.25' }},
+                f blob {{ x'00ff' }},
+                g bigint {{ null }}
+            );",
+            &[],
+            None,
+            &mut registry,
+        )
+        .unwrap();
+        let ctx = CompileContext::new(template.variables_count);
+        let tables = template
+            .tables
+            .into_iter()
+            .map(|t| ctx.compile_table(t))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut state = State::new(1, Box::new(StepRng::new(0, 1)), ctx);
+
+        let database = SqliteDatabase::open(Path::new(":memory:")).unwrap();
+        database
+            .execute_batch(&format!("CREATE TABLE t {}", tables[0].content))
+            .unwrap();
+        let options = Options {
+            dialect: Some(Dialect::SQLite),
+            transaction_size: NonZeroU64::new(2),
+            ..Options::default()
+        };
+        let mut env = Env::new(&tables, &mut state, false, |table| {
+            Ok(SqliteWriter::new(&database, &table.schema(false), &options))
+        })
+        .unwrap();
+        for _ in 0..3 {
+            for _ in 0..2 {
+                env.write_row().unwrap();
+            }
+            env.write_trailer().unwrap();
+        }
+        for (table, writer) in env.tables() {
+            writer.write_file_trailer(&table.schema(false)).unwrap();
+        }
+
+        let connection = database.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare("SELECT a, b, c, d, e, f, g FROM t ORDER BY a")
+            .unwrap();
+        let rows = statement
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, bool>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, Vec<u8>>(5)?,
+                    row.get::<_, Option<i64>>(6)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(rows.len(), 6);
+        for (i, row) in (1..).zip(&rows) {
+            assert_eq!(row.0, i64::from(i));
+            assert_eq!(row.1, format!("x{i}"));
+            assert_eq!(row.2, i == 2);
+            assert!((row.3 - f64::from(i) / 2.0).abs() < f64::EPSILON);
+            assert_eq!(row.4, "2001-04-19 18:08:51.250000");
+            assert_eq!(row.5, b"\x00\xff");
+            assert_eq!(row.6, None);
+        }
+    }
+
+    #[test]
+    fn test_integer_out_of_range() {
+        assert_eq!(
+            to_sqlite_value(&Value::from(i64::MAX)),
+            Some(SqliteValue::Integer(i64::MAX))
+        );
+        assert_eq!(
+            to_sqlite_value(&Value::from(i64::MIN)),
+            Some(SqliteValue::Integer(i64::MIN))
+        );
+        assert_eq!(to_sqlite_value(&Value::from(u64::MAX)), None);
+        assert_eq!(to_sqlite_value(&Value::from(i128::MIN)), None);
+    }
+}
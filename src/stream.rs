@@ -0,0 +1,156 @@
+//! Streaming the data files into a single output with `--stream`, `--rows-per-second` and
+//! `--bytes-per-second`.
+//!
+//! Every file generator thread sends the content of its files in chunks to a single thread writing
+//! them in order to the standard output or a TCP connection, and the generation can be throttled to
+//! a fixed rate.
+
+use std::{
+    io::{self, Write},
+    mem,
+    net::TcpStream,
+    sync::mpsc::{Receiver, SyncSender},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+/// Maximum number of chunks of every file waiting to be written to the standard output.
+pub const STDOUT_CHUNKS_PER_FILE: usize = 16;
+
+/// A writer sending the content of a data file to the thread writing to the standard output.
+///
+/// The bytes are collected until the writer is flushed, which happens after every INSERT
+/// statement, so statements of different tables of the same file may be interleaved in the
+/// standard output but are never split.
+#[derive(Debug)]
+pub struct StdoutChunkWriter {
+    /// The bytes written since the last flush.
+    buffer: Vec<u8>,
+    /// The sender to the thread writing to the standard output.
+    sender: SyncSender<Vec<u8>>,
+}
+
+impl StdoutChunkWriter {
+    /// Creates a writer sending the content of a data file through the sender.
+    pub fn new(sender: SyncSender<Vec<u8>>) -> Self {
+        Self {
+            buffer: Vec::new(),
+            sender,
+        }
+    }
+}
+
+impl Write for StdoutChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.sender
+            .send(mem::take(&mut self.buffer))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "standard output is closed"))
+    }
+}
+
+/// Writes the chunks of every data file to the TCP connection if given, or otherwise the standard
+/// output, in the order the channels of the files are received.
+///
+/// A file is completely written when all senders of its channel are dropped.
+pub fn write_chunks_to_output(tcp: Option<TcpStream>, receivers: Receiver<Receiver<Vec<u8>>>) -> io::Result<()> {
+    let mut output: Box<dyn Write> = match tcp {
+        Some(tcp) => Box::new(tcp),
+        None => Box::new(io::stdout().lock()),
+    };
+    for receiver in receivers {
+        for chunk in receiver {
+            output.write_all(&chunk)?;
+        }
+    }
+    output.flush()
+}
+
+/// Limits how fast the rows are generated, across all file generator threads.
+#[derive(Debug)]
+pub struct RateLimit {
+    /// When the generation started.
+    start: Instant,
+    /// Maximum number of rows of the main table generated per second.
+    rows_per_second: Option<u64>,
+    /// Maximum number of bytes written per second.
+    bytes_per_second: Option<u64>,
+}
+
+impl RateLimit {
+    /// Creates a rate limit starting now, or `None` if neither limit is given.
+    pub fn new(rows_per_second: Option<u64>, bytes_per_second: Option<u64>) -> Option<Self> {
+        (rows_per_second.is_some() || bytes_per_second.is_some()).then(|| Self {
+            start: Instant::now(),
+            rows_per_second,
+            bytes_per_second,
+        })
+    }
+
+    /// Sleeps until the number of rows of the main table and bytes written so far are within the
+    /// limits.
+    pub fn wait(&self, rows_count: u64, size: u64) {
+        let delay = self.delay(rows_count, size, self.start.elapsed());
+        if !delay.is_zero() {
+            sleep(delay);
+        }
+    }
+
+    /// Computes how long to sleep for the rows and bytes written so far to be within the limits,
+    /// when the given duration has elapsed since the start.
+    fn delay(&self, rows_count: u64, size: u64, elapsed: Duration) -> Duration {
+        let min_duration = |count: u64, per_second: Option<u64>| {
+            per_second.map_or(Duration::ZERO, |per_second| {
+                let nanos = u128::from(count) * 1_000_000_000 / u128::from(per_second);
+                Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+            })
+        };
+        let min_elapsed = min_duration(rows_count, self.rows_per_second).max(min_duration(size, self.bytes_per_second));
+        min_elapsed.saturating_sub(elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit() {
+        assert!(RateLimit::new(None, None).is_none());
+
+        let rows_limit = RateLimit::new(Some(100), None).unwrap();
+        assert_eq!(rows_limit.delay(0, 0, Duration::ZERO), Duration::ZERO);
+        assert_eq!(
+            rows_limit.delay(30, u64::MAX, Duration::ZERO),
+            Duration::from_millis(300)
+        );
+        assert_eq!(
+            rows_limit.delay(30, 0, Duration::from_millis(120)),
+            Duration::from_millis(180)
+        );
+        assert_eq!(rows_limit.delay(30, 0, Duration::from_secs(1)), Duration::ZERO);
+
+        // The stricter limit wins.
+        let both_limits = RateLimit::new(Some(100), Some(1000)).unwrap();
+        assert_eq!(both_limits.delay(30, 1000, Duration::ZERO), Duration::from_secs(1));
+        assert_eq!(both_limits.delay(300, 1000, Duration::ZERO), Duration::from_secs(3));
+        assert_eq!(both_limits.delay(300, 1000, Duration::from_secs(5)), Duration::ZERO);
+
+        let bytes_limit = RateLimit::new(None, Some(3)).unwrap();
+        assert_eq!(
+            bytes_limit.delay(u64::MAX, 1, Duration::ZERO),
+            Duration::from_nanos(333_333_333)
+        );
+        assert_eq!(
+            bytes_limit.delay(0, u64::MAX, Duration::ZERO),
+            Duration::from_nanos(u64::MAX)
+        );
+    }
+}
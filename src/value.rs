@@ -19,9 +19,10 @@ use crate::{
 pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
 
 /// A scalar value.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Default)]
 pub enum Value {
     /// Null.
+    #[default]
     Null,
     /// A number.
     Number(Number),
@@ -35,12 +36,6 @@ pub enum Value {
     Array(Array),
 }
 
-impl Default for Value {
-    fn default() -> Self {
-        Self::Null
-    }
-}
-
 macro_rules! try_or_overflow {
     ($e:expr, $($fmt:tt)+) => {
         if let Some(e) = $e {
@@ -121,7 +116,7 @@ impl Value {
     /// * Strings are ordered by UTF-8 binary collation.
     /// * Arrays are ordered lexicographically.
     /// * Comparing between different types are inconsistent among database
-    ///     engines, thus this function will just error with `InvalidArguments`.
+    ///   engines, thus this function will just error with `InvalidArguments`.
     pub fn sql_cmp(&self, other: &Self) -> Result<Option<Ordering>, Error> {
         Ok(match (self, other) {
             (Self::Null, _) | (_, Self::Null) => None,
@@ -2,7 +2,7 @@
 
 use crate::{
     error::Error,
-    eval::{Schema, State, Table},
+    eval::{RowKey, Schema, State, Table},
     span::{ResultExt, S},
     value::Value,
 };
@@ -26,10 +26,13 @@ pub trait Writer {
     fn write_value_separator(&mut self) -> Result<(), S<Error>>;
 
     /// Writes the separator between the every row.
-    fn write_row_separator(&mut self) -> Result<(), S<Error>>;
+    fn write_row_separator(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>>;
 
     /// Writes the content of an INSERT statement after all rows.
-    fn write_trailer(&mut self) -> Result<(), S<Error>>;
+    fn write_trailer(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>>;
+
+    /// Writes the content at the end of each file.
+    fn write_file_trailer(&mut self, schema: &Schema<'_>) -> Result<(), S<Error>>;
 }
 
 /// The state of a table within [`Env`].
@@ -91,15 +94,16 @@ impl<'a, W: Writer> Env<'a, W> {
         self.tables.iter_mut().map(|table| (table.table, &mut table.writer))
     }
 
-    fn write_one_row(&mut self, table_index: usize) -> Result<(), S<Error>> {
+    fn write_one_row(&mut self, table_index: usize, row_key: RowKey) -> Result<(), S<Error>> {
         let table = &mut self.tables[table_index];
 
         if mem::take(&mut table.empty) {
             table.writer.write_header(&table.schema)
         } else {
-            table.writer.write_row_separator()
+            table.writer.write_row_separator(&table.schema)
         }?;
 
+        self.state.seed_row(row_key);
         let values = table.table.row.eval(self.state)?;
 
         for (col_index, (column, value)) in table.schema.column_names().zip(&values).enumerate() {
@@ -115,7 +119,7 @@ impl<'a, W: Writer> Env<'a, W> {
 
             for r in 1..=count {
                 self.state.sub_row_num = r;
-                self.write_one_row(*child)?;
+                self.write_one_row(*child, row_key.child(*child, r))?;
             }
         }
 
@@ -131,7 +135,8 @@ impl<'a, W: Writer> Env<'a, W> {
         }
     }
 
-    /// Writes one row from each root table
+    /// Writes one row from each root table, unless the root table has its own row count which is
+    /// already reached.
     pub fn write_row(&mut self) -> Result<(), S<Error>> {
         for table in &mut self.tables {
             table.fresh = true;
@@ -139,8 +144,15 @@ impl<'a, W: Writer> Env<'a, W> {
         for i in 0..self.tables.len() {
             if self.tables[i].fresh {
                 self.mark_descendant_visited(i);
+                if self.tables[i]
+                    .table
+                    .rows_count
+                    .is_some_and(|rows_count| self.state.row_num > rows_count)
+                {
+                    continue;
+                }
                 self.state.sub_row_num = 1;
-                self.write_one_row(i)?;
+                self.write_one_row(i, RowKey::default().child(i, self.state.row_num))?;
             }
         }
         self.state.increase_row_num();
@@ -154,7 +166,7 @@ impl<'a, W: Writer> Env<'a, W> {
     pub fn write_trailer(&mut self) -> Result<(), S<Error>> {
         for table in &mut self.tables {
             if !mem::replace(&mut table.empty, true) {
-                table.writer.write_trailer()?;
+                table.writer.write_trailer(&table.schema)?;
             }
         }
         Ok(())
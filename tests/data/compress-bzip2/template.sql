@@ -0,0 +1 @@
+CREATE TABLE result ({{ rownum }} {{ rownum * 7 }});
\ No newline at end of file
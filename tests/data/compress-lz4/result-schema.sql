@@ -0,0 +1 @@
+CREATE TABLE result ();
\ No newline at end of file
@@ -0,0 +1,5 @@
+CREATE TABLE result (
+    "id" int,
+    "a;b" text,
+    "c" float,
+    "d" text);
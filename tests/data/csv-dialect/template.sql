@@ -0,0 +1,6 @@
+create table result (
+    "id" int {{ rownum }},
+    "a;b" text {{ case rownum when 1 then 'x;y' when 2 then 'q"z' when 3 then '' else null end }},
+    "c" float {{ 1.5 }},
+    "d" text {{ 'plain' }}
+);
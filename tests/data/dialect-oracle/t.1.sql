@@ -0,0 +1,6 @@
+INSERT INTO t VALUES
+(1, 'it''s 1');
+INSERT INTO t VALUES
+(2, 'it''s 2');
+INSERT INTO t VALUES
+(3, 'it''s 3');
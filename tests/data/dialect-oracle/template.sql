@@ -0,0 +1,4 @@
+CREATE TABLE t (
+    a INT {{ rownum }},
+    b VARCHAR2(20) {{ 'it''s ' || rownum }}
+);
@@ -0,0 +1,3 @@
+INSERT INTO [odd]]name] (a, [b `quoted`], c, d, e) VALUES
+(1, N'it''s 1', 1, CAST('2001-04-19T18:08:51.250000' AS DATETIME2), 0x00FF),
+(2, N'it''s 2', 0, CAST('2001-04-19T18:08:51.250000' AS DATETIME2), 0x00FF);
@@ -0,0 +1,7 @@
+CREATE TABLE "odd]name" (
+    a INT {{ rownum }},
+    `b ``quoted``` NVARCHAR(20) {{ 'it''s ' || rownum }},
+    c BIT {{ rownum = 1 }},
+    d DATETIME2 {{ TIMESTAMP '2001-04-19 18:08:51.25' }},
+    e VARBINARY(2) {{ x'00ff' }}
+);
@@ -0,0 +1,4 @@
+INSERT INTO result VALUES
+(4, 0, 4),
+(5, 5, 1),
+(6, 5, 8);
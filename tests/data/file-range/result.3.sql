@@ -0,0 +1,4 @@
+INSERT INTO result VALUES
+(7, 7, 10),
+(8, 7, 4),
+(9, 5, 2);
@@ -0,0 +1,12 @@
+CREATE TABLE result (
+    a INT {{ rownum }},
+    "b ""quoted""" TEXT {{ 'line' || x'0a' || 'tab' || x'09' || '"q" \ é' }},
+    `c` BOOLEAN {{ rownum = 1 }},
+    d FLOAT {{ 0.5 }},
+    e TIMESTAMP {{ TIMESTAMP '2001-04-19 18:08:51.25' }},
+    f INTERVAL {{ INTERVAL 500 SECOND }},
+    g BLOB {{ x'00ff' }},
+    h TEXT {{ NULL }},
+    i TEXT {{ '' }},
+    [j] INT[] {{ array[1, null, array['x']] }}
+);
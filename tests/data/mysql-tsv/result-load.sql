@@ -0,0 +1 @@
+LOAD DATA LOCAL INFILE 'result.1.tsv' INTO TABLE result CHARACTER SET binary FIELDS TERMINATED BY '\t' ESCAPED BY '\\' LINES TERMINATED BY '\n' IGNORE 1 LINES (`a`, `b ``quoted```, `c`, `d`, `e`, `f`, `g`, `h`, `i`);
@@ -0,0 +1,11 @@
+CREATE TABLE result (
+    a INT {{ rownum }},
+    `b ``quoted``` TEXT {{ 'line' || x'0a' || 'tab' || x'09' || '\ é' }},
+    c BOOLEAN {{ rownum = 1 }},
+    d DOUBLE {{ 0.5 }},
+    e DATETIME(6) {{ TIMESTAMP '2001-04-19 18:08:51.25' }},
+    f BLOB {{ x'005c090a0dff' }},
+    g TEXT {{ NULL }},
+    h TEXT {{ '\N' }},
+    i JSON {{ array[1, null, 'x'] }}
+);
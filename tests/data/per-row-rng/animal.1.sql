@@ -0,0 +1,6 @@
+INSERT INTO animal VALUES
+(1, 1, 13),
+(2, 1, 49);
+INSERT INTO animal VALUES
+(3, 1, 25),
+(4, 1, 20);
@@ -0,0 +1,6 @@
+INSERT INTO animal VALUES
+(5, 1, 9),
+(6, 1, 26);
+INSERT INTO animal VALUES
+(7, 1, 57),
+(8, 1, 24);
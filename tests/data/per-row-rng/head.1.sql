@@ -0,0 +1,6 @@
+INSERT INTO head VALUES
+(1, 1, 1300),
+(2, 1, 4900);
+INSERT INTO head VALUES
+(3, 1, 2500),
+(4, 1, 2000);
@@ -0,0 +1,6 @@
+INSERT INTO head VALUES
+(5, 1, 900),
+(6, 1, 2600);
+INSERT INTO head VALUES
+(7, 1, 5700),
+(8, 1, 2400);
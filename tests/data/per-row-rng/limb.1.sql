@@ -0,0 +1,18 @@
+INSERT INTO limb VALUES
+(1, 1, 13, 0),
+(1, 2, 13, 0),
+(1, 3, 13, 0),
+(1, 4, 13, 0),
+(2, 1, 49, 0),
+(2, 2, 49, 0),
+(2, 3, 49, 1),
+(2, 4, 49, 1);
+INSERT INTO limb VALUES
+(3, 1, 25, 2),
+(3, 2, 25, 2),
+(3, 3, 25, 2),
+(3, 4, 25, 1),
+(4, 1, 20, 3),
+(4, 2, 20, 3),
+(4, 3, 20, 1),
+(4, 4, 20, 3);
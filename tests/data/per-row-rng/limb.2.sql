@@ -0,0 +1,18 @@
+INSERT INTO limb VALUES
+(5, 1, 9, 0),
+(5, 2, 9, 2),
+(5, 3, 9, 0),
+(5, 4, 9, 4),
+(6, 1, 26, 1),
+(6, 2, 26, 0),
+(6, 3, 26, 0),
+(6, 4, 26, 1);
+INSERT INTO limb VALUES
+(7, 1, 57, 0),
+(7, 2, 57, 1),
+(7, 3, 57, 1),
+(7, 4, 57, 4),
+(8, 1, 24, 0),
+(8, 2, 24, 0),
+(8, 3, 24, 5),
+(8, 4, 24, 5);
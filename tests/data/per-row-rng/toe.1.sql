@@ -0,0 +1,21 @@
+INSERT INTO toe VALUES
+(2, 3, 1, 49),
+(2, 4, 1, 49);
+INSERT INTO toe VALUES
+(3, 1, 1, 25),
+(3, 1, 2, 25),
+(3, 2, 1, 25),
+(3, 2, 2, 25),
+(3, 3, 1, 25),
+(3, 3, 2, 25),
+(3, 4, 1, 25),
+(4, 1, 1, 20),
+(4, 1, 2, 20),
+(4, 1, 3, 20),
+(4, 2, 1, 20),
+(4, 2, 2, 20),
+(4, 2, 3, 20),
+(4, 3, 1, 20),
+(4, 4, 1, 20),
+(4, 4, 2, 20),
+(4, 4, 3, 20);
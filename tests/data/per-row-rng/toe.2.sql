@@ -0,0 +1,26 @@
+INSERT INTO toe VALUES
+(5, 2, 1, 9),
+(5, 2, 2, 9),
+(5, 4, 1, 9),
+(5, 4, 2, 9),
+(5, 4, 3, 9),
+(5, 4, 4, 9),
+(6, 1, 1, 26),
+(6, 4, 1, 26);
+INSERT INTO toe VALUES
+(7, 2, 1, 57),
+(7, 3, 1, 57),
+(7, 4, 1, 57),
+(7, 4, 2, 57),
+(7, 4, 3, 57),
+(7, 4, 4, 57),
+(8, 3, 1, 24),
+(8, 3, 2, 24),
+(8, 3, 3, 24),
+(8, 3, 4, 24),
+(8, 3, 5, 24),
+(8, 4, 1, 24),
+(8, 4, 2, 24),
+(8, 4, 3, 24),
+(8, 4, 4, 24),
+(8, 4, 5, 24);
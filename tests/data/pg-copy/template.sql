@@ -0,0 +1,12 @@
+CREATE TABLE result (
+    a INT {{ rownum }},
+    "b ""quoted""" TEXT {{ 'line' || x'0a' || 'tab' || x'09' || 'cr' || x'0d' || '\ é' }},
+    c BOOLEAN {{ rownum = 1 }},
+    d NUMERIC {{ 0.5 }},
+    e TIMESTAMP {{ TIMESTAMP '2001-04-19 18:08:51.25' }},
+    f INTERVAL {{ INTERVAL (rownum - 3) * 500 SECOND }},
+    g BYTEA {{ x'00ff' }},
+    h TEXT {{ NULL }},
+    i TEXT {{ '' }},
+    j TEXT[] {{ array['a"b', null, 'c\d'] }}
+);
@@ -0,0 +1,3 @@
+CREATE TABLE customer (
+    c_id INT,
+    c_w_id INT);
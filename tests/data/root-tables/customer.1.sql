@@ -0,0 +1,6 @@
+INSERT INTO customer VALUES
+(1, 1),
+(2, 1);
+INSERT INTO customer VALUES
+(3, 1),
+(4, 1);
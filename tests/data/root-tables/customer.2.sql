@@ -0,0 +1,6 @@
+INSERT INTO customer VALUES
+(5, 1),
+(6, 2);
+INSERT INTO customer VALUES
+(7, 2),
+(8, 2);
@@ -0,0 +1,3 @@
+INSERT INTO customer VALUES
+(9, 2),
+(10, 2);
@@ -0,0 +1,4 @@
+CREATE TABLE district (
+    d_w_id INT,
+    d_id INT);
+
@@ -0,0 +1,5 @@
+INSERT INTO district VALUES
+(1, 1),
+(1, 2),
+(2, 1),
+(2, 2);
@@ -0,0 +1,3 @@
+CREATE TABLE item (
+    i_id INT);
+
@@ -0,0 +1,6 @@
+INSERT INTO item VALUES
+(1),
+(2);
+INSERT INTO item VALUES
+(3),
+(4);
@@ -0,0 +1,2 @@
+INSERT INTO item VALUES
+(5);
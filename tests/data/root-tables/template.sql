@@ -0,0 +1,21 @@
+/*{{ generate @w rows of warehouse }}*/
+CREATE TABLE warehouse (
+    w_id INT {{ rownum }}
+);
+
+/*{{ for each row of warehouse generate 2 rows of district }}*/
+CREATE TABLE district (
+    d_w_id INT {{ rownum }},
+    d_id INT {{ subrownum }}
+);
+
+/*{{ generate 100 rows of item }}*/
+CREATE TABLE item (
+    i_id INT {{ rownum }}
+);
+
+/*{{ generate @w * 5 rows of customer }}*/
+CREATE TABLE customer (
+    c_id INT {{ rownum }},
+    c_w_id INT {{ div(rownum - 1, 5) + 1 }}
+);
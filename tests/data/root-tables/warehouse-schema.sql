@@ -0,0 +1,3 @@
+CREATE TABLE warehouse (
+    w_id INT);
+
@@ -0,0 +1,3 @@
+INSERT INTO warehouse VALUES
+(1),
+(2);
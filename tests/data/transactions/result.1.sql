@@ -0,0 +1,19 @@
+SET foreign_key_checks = 0;
+SET unique_checks = 0;
+BEGIN;
+INSERT INTO result VALUES
+(1);
+INSERT INTO result VALUES
+(2);
+COMMIT;
+BEGIN;
+INSERT INTO result VALUES
+(3);
+INSERT INTO result VALUES
+(4);
+COMMIT;
+BEGIN;
+INSERT INTO result VALUES
+(5);
+COMMIT;
+SET foreign_key_checks = 1;
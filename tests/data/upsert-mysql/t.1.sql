@@ -0,0 +1,4 @@
+INSERT INTO t (id, `x`, `y`) VALUES
+(1, 'it''s\\', TRUE),
+(2, 'it''s\\', FALSE)
+ON DUPLICATE KEY UPDATE `x` = VALUES(`x`), `y` = VALUES(`y`);
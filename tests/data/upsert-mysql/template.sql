@@ -0,0 +1,5 @@
+CREATE TABLE t (
+    id INT {{ rownum }},
+    `x` TEXT {{ 'it''s\' }},
+    "y" BOOLEAN {{ rownum = 1 }}
+);
@@ -0,0 +1,4 @@
+INSERT INTO a VALUES
+(1, 'x1', TRUE),
+(2, 'x2', FALSE)
+ON CONFLICT (id) DO UPDATE SET x = EXCLUDED.x, y = EXCLUDED.y;
@@ -0,0 +1,4 @@
+INSERT INTO b VALUES
+(1, 1, 'v', 'w'),
+(2, 1, 'v', 'w')
+ON CONFLICT (k1, "K2") DO UPDATE SET v = EXCLUDED.v;
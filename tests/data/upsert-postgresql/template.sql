@@ -0,0 +1,12 @@
+CREATE TABLE a (
+    id INT {{ rownum }},
+    x TEXT {{ 'x' || rownum }},
+    y BOOLEAN {{ rownum = 1 }}
+);
+{{ for each row of a generate 1 row of b }}
+CREATE TABLE b (
+    k1 INT {{ rownum }},
+    "K2" INT {{ subrownum }},
+    v TEXT {{ 'v' }},
+    w TEXT {{ 'w' }}
+);